@@ -0,0 +1,350 @@
+use std::{fmt, path::PathBuf, str::FromStr};
+
+use anyhow::bail;
+
+use crate::{
+    problems::Answer,
+    runner::{Outcome, Report},
+};
+
+/// The correct answers for a day's input, as far as we know them.
+///
+/// Stored in `answers/dayNN.txt` as `part one:` and `part two:` sections. An
+/// answer can either follow on the same line, or take up the lines up to the
+/// next section, for answers like day 10's picture:
+///
+/// ```text
+/// part one: 15260
+/// part two:
+/// ###...##..
+/// #..#.#..#.
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Known {
+    pub part_one: Option<Answer>,
+    pub part_two: Option<Answer>,
+}
+
+pub fn answers_path(day: usize) -> PathBuf {
+    format!("answers/day{:02}.txt", day).into()
+}
+
+impl Known {
+    /// The stored answers for `day`, or none if there's no answers file
+    pub fn load(day: usize) -> anyhow::Result<Self> {
+        let path = answers_path(day);
+        if !path.exists() {
+            return Ok(Known::default());
+        }
+        let text = std::fs::read_to_string(&path)?;
+        text.parse()
+            .map_err(|e: anyhow::Error| e.context(format!("Reading {}", path.display())))
+    }
+}
+
+impl FromStr for Known {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: [Option<String>; 2] = [None, None];
+        let mut current = None;
+
+        for line in s.lines() {
+            let header = ["part one:", "part two:"]
+                .iter()
+                .enumerate()
+                .find_map(|(i, prefix)| line.strip_prefix(prefix).map(|rest| (i, rest)));
+            match (header, current) {
+                (Some((i, rest)), _) => {
+                    if parts[i].is_some() {
+                        bail!("Repeated section '{line}'");
+                    }
+                    parts[i] = Some(rest.trim().to_owned());
+                    current = Some(i);
+                }
+                (None, Some(i)) => {
+                    let text = parts[i].get_or_insert_with(String::new);
+                    if !text.is_empty() {
+                        text.push('\n');
+                    }
+                    text.push_str(line);
+                }
+                (None, None) if line.trim().is_empty() => {}
+                (None, None) => bail!("Expected 'part one:' or 'part two:', got '{line}'"),
+            }
+        }
+
+        let [part_one, part_two] =
+            parts.map(|p| p.map(|s| Answer::from_str(&s).unwrap_or_else(|e| match e {})));
+        Ok(Known { part_one, part_two })
+    }
+}
+
+/// How an outcome compares to the known answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    Fail {
+        expected: Answer,
+    },
+    Unknown,
+    /// The stored answers couldn't be read
+    Error(String),
+}
+
+impl Check {
+    pub fn new(expected: Option<&Answer>, outcome: &Outcome) -> Self {
+        match (expected, outcome) {
+            (None, _)
+            | (_, Outcome::Stub | Outcome::NoInput | Outcome::Skipped | Outcome::NoPuzzle) => {
+                Check::Unknown
+            }
+            (Some(expected), Outcome::Answer(answer)) if answer == expected => Check::Pass,
+            (Some(expected), _) => Check::Fail {
+                expected: expected.clone(),
+            },
+        }
+    }
+
+    /// Check both parts of a report against the stored answers. Those are
+    /// only for our own input, so anything else, like an example, is unknown.
+    pub fn report(report: &Report) -> anyhow::Result<[Check; 2]> {
+        if report.input != crate::input_path(report.day) {
+            return Ok([Check::Unknown, Check::Unknown]);
+        }
+        let known = Known::load(report.day)?;
+        Ok([
+            Check::new(known.part_one.as_ref(), &report.part_one),
+            Check::new(known.part_two.as_ref(), &report.part_two),
+        ])
+    }
+
+    /// Whether this counts against the run, as a wrong answer or an
+    /// unreadable answers file
+    pub fn is_mismatch(&self) -> bool {
+        matches!(self, Check::Fail { .. } | Check::Error(_))
+    }
+
+    /// What kind of check this is, for machine-readable output
+    pub fn status(&self) -> &'static str {
+        match self {
+            Check::Pass => "pass",
+            Check::Fail { .. } => "fail",
+            Check::Unknown => "unknown",
+            Check::Error(_) => "error",
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Check::Pass => f.write_str("pass"),
+            Check::Fail { .. } => f.write_str("FAIL"),
+            Check::Unknown => f.write_str("unknown"),
+            Check::Error(e) => write!(f, "error: {e}"),
+        }
+    }
+}
+
+/// Check each part of each report against the stored answers, in the same
+/// order as the reports. Parts that weren't run, and days without an input,
+/// are unknown.
+pub fn check_reports(reports: &[Report]) -> Vec<[Check; 2]> {
+    reports
+        .iter()
+        .map(|report| {
+            if !report.has_input() {
+                return [Check::Unknown, Check::Unknown];
+            }
+            let checks = Check::report(report).unwrap_or_else(|e| {
+                let e = format!("{e:#}");
+                [Check::Error(e.clone()), Check::Error(e)]
+            });
+            let skipped = |check, outcome: &Outcome| match outcome {
+                Outcome::Skipped => Check::Unknown,
+                _ => check,
+            };
+            let [one, two] = checks;
+            [
+                skipped(one, &report.part_one),
+                skipped(two, &report.part_two),
+            ]
+        })
+        .collect()
+}
+
+/// Whether none of the checks found a mismatch
+pub fn all_pass(checks: &[[Check; 2]]) -> bool {
+    !checks.iter().flatten().any(Check::is_mismatch)
+}
+
+/// Print how each part of each report compares to the stored answers, from
+/// [`check_reports`]
+pub fn print_checks(reports: &[Report], checks: &[[Check; 2]]) {
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+    for (report, checks) in reports.iter().zip(checks) {
+        if !report.has_input() {
+            continue;
+        }
+        let outcomes = [&report.part_one, &report.part_two];
+        for ((name, check), outcome) in ["one", "two"].into_iter().zip(checks).zip(outcomes) {
+            if *outcome == Outcome::Skipped {
+                continue;
+            }
+            print!(
+                "Day {} part {name} ({}): {check}",
+                report.day,
+                report.input_name()
+            );
+            match check {
+                Check::Pass => passed += 1,
+                Check::Unknown => unknown += 1,
+                Check::Error(_) => failed += 1,
+                Check::Fail { expected } if expected.is_multiline() => {
+                    println!(", expected:\n{expected}");
+                    failed += 1;
+                    continue;
+                }
+                Check::Fail { expected } => {
+                    print!(", expected {expected}");
+                    failed += 1;
+                }
+            }
+            println!();
+        }
+    }
+    println!("{passed} passed, {failed} failed, {unknown} unknown");
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+    use crate::runner::{run_day, Parts};
+
+    #[test]
+    fn test_parse() {
+        let known: Known = "part one: 15260\npart two:\n##..\n#..#\n".parse().unwrap();
+        assert_eq!(known.part_one, Some(Answer::Int(15260)));
+        assert_eq!(known.part_two, Some(Answer::grid("##..\n#..#")));
+
+        let known: Known = "part two: abc\n".parse().unwrap();
+        assert_eq!(known.part_one, None);
+        assert_eq!(known.part_two, Some(Answer::from("abc")));
+
+        assert!("12\n".parse::<Known>().is_err());
+        assert!("part one: 1\npart one: 2\n".parse::<Known>().is_err());
+    }
+
+    #[test]
+    fn test_check_other_input() {
+        let report = Report {
+            day: 1,
+            input: "inputs/examples/day01.txt".into(),
+            parsed: true,
+            part_one: Outcome::Answer(Answer::Int(24000)),
+            part_two: Outcome::Answer(Answer::Int(45000)),
+            timings: Default::default(),
+        };
+        assert_eq!(
+            Check::report(&report).unwrap(),
+            [Check::Unknown, Check::Unknown]
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let crt = Answer::grid("##..\n#..#");
+        let twelve = Answer::Int(12);
+        let answer = Outcome::Answer(crt.clone());
+        assert_eq!(Check::new(Some(&crt), &answer), Check::Pass);
+        assert_eq!(
+            Check::new(Some(&twelve), &answer),
+            Check::Fail {
+                expected: twelve.clone()
+            }
+        );
+        assert_eq!(Check::new(None, &answer), Check::Unknown);
+        assert_eq!(Check::new(Some(&twelve), &Outcome::Stub), Check::Unknown);
+        assert_eq!(
+            Check::new(Some(&twelve), &Outcome::Panic("Oops".to_owned())),
+            Check::Fail {
+                expected: twelve.clone()
+            }
+        );
+        // Compared by value, not by how the answer was formatted
+        let stored: Known = "part one: 012\n".parse().unwrap();
+        let answer = Outcome::Answer(twelve.clone());
+        assert_eq!(Check::new(stored.part_one.as_ref(), &answer), Check::Pass);
+
+        // Day 25's SNAFU answers are text, even when they're all digits
+        let stored: Known = "part one: 1121\n".parse().unwrap();
+        let answer = Outcome::Answer(Answer::from("1121"));
+        assert_eq!(Check::new(stored.part_one.as_ref(), &answer), Check::Pass);
+    }
+
+    /// Check the stored answers for a day, if its input is present
+    fn check_stored(day: usize) {
+        let input = crate::input_path(day);
+        let report = run_day(day, &input, Parts::Both, 1, None);
+        if !report.has_input() {
+            log::info!("No input for day {day}, skipping");
+            return;
+        }
+
+        let known = Known::load(day).unwrap();
+        for (expected, outcome) in [
+            (known.part_one, report.part_one),
+            (known.part_two, report.part_two),
+        ] {
+            if let Some(expected) = expected {
+                assert_eq!(
+                    Check::new(Some(&expected), &outcome),
+                    Check::Pass,
+                    "{outcome}"
+                );
+            }
+        }
+    }
+
+    macro_rules! stored_answer_tests {
+        ($($name:ident => $day:expr),* $(,)?) => {
+            $(
+                #[test]
+                fn $name() {
+                    check_stored($day);
+                }
+            )*
+        };
+    }
+
+    stored_answer_tests!(
+        test_day01 => 1,
+        test_day02 => 2,
+        test_day03 => 3,
+        test_day04 => 4,
+        test_day05 => 5,
+        test_day06 => 6,
+        test_day07 => 7,
+        test_day08 => 8,
+        test_day09 => 9,
+        test_day10 => 10,
+        test_day11 => 11,
+        test_day12 => 12,
+        test_day13 => 13,
+        test_day14 => 14,
+        test_day15 => 15,
+        test_day16 => 16,
+        test_day17 => 17,
+        test_day18 => 18,
+        test_day19 => 19,
+        test_day20 => 20,
+        test_day21 => 21,
+        test_day22 => 22,
+        test_day23 => 23,
+        test_day24 => 24,
+        test_day25 => 25,
+    );
+}
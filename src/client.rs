@@ -0,0 +1,259 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context};
+
+pub const YEAR: usize = 2022;
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+// The site asks automated tools to say who they are, and where to find them
+const USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/wackywendell/adventofcode2022)"
+);
+
+/// Environment variables for the session cookie, and for pointing the client
+/// somewhere other than the real site
+const SESSION_VAR: &str = "AOC_SESSION";
+const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+/// Where the session cookie is kept if it's not in the environment
+pub fn session_path() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("adventofcode").join("session"))
+}
+
+/// Find the session cookie, from `$AOC_SESSION` or the session file
+pub fn find_session() -> anyhow::Result<String> {
+    if let Ok(session) = env::var(SESSION_VAR) {
+        return Ok(session.trim().to_owned());
+    }
+    let path = session_path().ok_or_else(|| anyhow!("No home directory for the session file"))?;
+    let session = fs::read_to_string(&path).with_context(|| {
+        format!(
+            "No session found: set ${SESSION_VAR}, or save the session cookie in {}",
+            path.display()
+        )
+    })?;
+    Ok(session.trim().to_owned())
+}
+
+/// A client for the Advent of Code site
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        let agent = ureq::AgentBuilder::new().user_agent(USER_AGENT).build();
+        Client {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.to_owned(),
+        }
+    }
+
+    /// A client for the site at `$AOC_BASE_URL` (the real one by default),
+    /// using the session from [`find_session`]
+    pub fn from_env() -> anyhow::Result<Self> {
+        let base_url = env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_owned());
+        Ok(Client::new(&base_url, &find_session()?))
+    }
+
+    fn day_url(&self, day: usize) -> String {
+        format!("{}/{YEAR}/day/{day}", self.base_url)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// Download the puzzle input for `day`
+    pub fn input(&self, day: usize) -> anyhow::Result<String> {
+        let url = format!("{}/input", self.day_url(day));
+        let response = match self.agent.get(&url).set("Cookie", &self.cookie()).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => bail!("Day {day} isn't available yet"),
+            Err(ureq::Error::Status(400 | 401, _)) => {
+                bail!("The site rejected the session cookie; it may have expired")
+            }
+            Err(e) => return Err(e).with_context(|| format!("Fetching {url}")),
+        };
+        let input = response
+            .into_string()
+            .with_context(|| format!("Reading {url}"))?;
+        if input.is_empty() {
+            bail!("The input for day {day} was empty");
+        }
+        Ok(input)
+    }
+
+    /// Submit `answer` for one part of `day`, returning the page the site
+    /// sends back
+    pub fn answer(&self, day: usize, part: usize, answer: &str) -> anyhow::Result<String> {
+        let url = format!("{}/answer", self.day_url(day));
+        let level = part.to_string();
+        let response = match self
+            .agent
+            .post(&url)
+            .set("Cookie", &self.cookie())
+            .send_form(&[("level", &level), ("answer", answer)])
+        {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => bail!("Day {day} isn't available yet"),
+            Err(ureq::Error::Status(400 | 401, _)) => {
+                bail!("The site rejected the session cookie; it may have expired")
+            }
+            Err(e) => return Err(e).with_context(|| format!("Posting to {url}")),
+        };
+        response
+            .into_string()
+            .with_context(|| format!("Reading {url}"))
+    }
+}
+
+/// Whether an input file already has something in it
+pub fn is_cached(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.len() > 0)
+}
+
+/// Download the input for `day` to `path`, unless it's already there.
+/// Returns whether it was downloaded.
+///
+/// The input is written next to `path` first and then renamed into place, so
+/// an interrupted write can't leave a partial file that looks cached.
+pub fn fetch_input(client: &Client, day: usize, path: &Path) -> anyhow::Result<bool> {
+    if is_cached(path) {
+        return Ok(false);
+    }
+    let input = client.input(day)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let partial = path.with_extension("part");
+    fs::write(&partial, input).with_context(|| format!("Writing {}", partial.display()))?;
+    fs::rename(&partial, path).with_context(|| format!("Writing {}", path.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    use test_log::test;
+
+    use super::*;
+
+    /// A request as the mock server saw it
+    #[derive(Debug, Clone, Default)]
+    pub struct Request {
+        pub line: String,
+        pub headers: Vec<String>,
+        pub body: String,
+    }
+
+    impl Request {
+        pub fn header(&self, name: &str) -> Option<&str> {
+            let prefix = format!("{}: ", name.to_lowercase());
+            self.headers.iter().find_map(|h| {
+                h.to_lowercase()
+                    .starts_with(&prefix)
+                    .then(|| &h[prefix.len()..])
+            })
+        }
+    }
+
+    /// Serve each of `responses` as (status, body) to one request in turn on a
+    /// local port, returning its URL and the requests it receives
+    pub fn mock_server(responses: Vec<(u16, String)>) -> (String, mpsc::Receiver<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (send, recv) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = Request::default();
+                reader.read_line(&mut request.line).unwrap();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    request.headers.push(line.to_owned());
+                }
+                let length = request
+                    .header("Content-Length")
+                    .map_or(0, |l| l.parse().unwrap());
+                let mut received = vec![0; length];
+                reader.read_exact(&mut received).unwrap();
+                request.body = String::from_utf8(received).unwrap();
+                send.send(request).unwrap();
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+
+        (url, recv)
+    }
+
+    #[test]
+    fn test_fetch_input() {
+        let (url, requests) = mock_server(vec![(200, "1\n2\n3\n".to_owned())]);
+        let client = Client::new(&url, "abc123");
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+        let path = dir.join("day01.txt");
+
+        assert!(fetch_input(&client, 1, &path).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+        assert!(!path.with_extension("part").exists());
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line.trim_end(), "GET /2022/day/1/input HTTP/1.1");
+        assert_eq!(request.header("Cookie"), Some("session=abc123"));
+        assert_eq!(request.header("User-Agent"), Some(USER_AGENT));
+
+        // Already cached, so the server isn't asked again
+        assert!(!fetch_input(&client, 1, &path).unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let (url, _requests) = mock_server(vec![
+            (404, "Not yet".to_owned()),
+            (400, "Bad session".to_owned()),
+            (500, "Oops".to_owned()),
+        ]);
+        let client = Client::new(&url, "abc123");
+
+        let err = client.input(25).unwrap_err();
+        assert!(err.to_string().contains("isn't available yet"), "{err}");
+        let err = client.input(1).unwrap_err();
+        assert!(err.to_string().contains("session"), "{err}");
+        // A server error isn't the session's fault
+        let err = client.input(2).unwrap_err();
+        assert!(!format!("{err:#}").contains("session"), "{err:#}");
+    }
+}
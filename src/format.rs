@@ -0,0 +1,313 @@
+use std::str::FromStr;
+
+use anyhow::bail;
+use serde_json::{json, Value};
+
+use crate::{
+    answers::Check,
+    runner::{Outcome, Report, Samples},
+};
+
+/// How to print the results of a run
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Format {
+    /// For people: the answers, then tables of timings and checks
+    #[default]
+    Text,
+    /// A JSON array with an object for each report
+    Json,
+    /// Tab-separated values, with a header and a row for each part
+    Tsv,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "tsv" => Ok(Format::Tsv),
+            _ => bail!("Expected 'text', 'json', or 'tsv', got '{s}'"),
+        }
+    }
+}
+
+/// The message for a part that went wrong
+fn error(outcome: &Outcome) -> Option<String> {
+    match outcome {
+        Outcome::Error(e) | Outcome::Panic(e) => Some(e.clone()),
+        Outcome::Timeout(limit) => Some(format!("Timed out after {limit:.1?}")),
+        _ => None,
+    }
+}
+
+fn samples_json(samples: &Samples) -> Value {
+    let nanos = |d: Option<std::time::Duration>| d.map(|d| d.as_nanos() as u64);
+    json!({
+        "runs": samples.len(),
+        "min_ns": nanos(samples.min()),
+        "median_ns": nanos(samples.median()),
+        "max_ns": nanos(samples.max()),
+    })
+}
+
+/// The answer a check expected, if it failed
+fn expected(check: &Check) -> Option<String> {
+    match check {
+        Check::Fail { expected } => Some(expected.to_string()),
+        _ => None,
+    }
+}
+
+fn check_json(check: &Check) -> Value {
+    let error = match check {
+        Check::Error(e) => Some(e),
+        _ => None,
+    };
+    json!({
+        "status": check.status(),
+        "expected": expected(check),
+        "error": error,
+    })
+}
+
+/// Each part of a report with its check, if there is one
+fn parts<'a>(
+    report: &'a Report,
+    checks: Option<&'a [Check; 2]>,
+) -> [(usize, &'a Outcome, &'a Samples, Option<&'a Check>); 2] {
+    [
+        (
+            1,
+            &report.part_one,
+            &report.timings.part_one,
+            checks.map(|c| &c[0]),
+        ),
+        (
+            2,
+            &report.part_two,
+            &report.timings.part_two,
+            checks.map(|c| &c[1]),
+        ),
+    ]
+}
+
+/// A report as JSON, with how it compares to the stored answers if `checks`
+/// are given. Answers are strings, so pictures keep their newlines.
+pub fn report_json(report: &Report, checks: Option<&[Check; 2]>) -> Value {
+    let parts: Vec<Value> = parts(report, checks)
+        .into_iter()
+        .map(|(part, outcome, samples, check)| {
+            let answer = match outcome {
+                Outcome::Answer(answer) => Some(answer.to_string()),
+                _ => None,
+            };
+            json!({
+                "part": part,
+                "status": outcome.status(),
+                "answer": answer,
+                "error": error(outcome),
+                "time": samples_json(samples),
+                "check": check.map(check_json),
+            })
+        })
+        .collect();
+
+    json!({
+        "day": report.day,
+        "input": report.input_name(),
+        "parse_time": samples_json(&report.timings.parse),
+        "parts": parts,
+    })
+}
+
+/// Print the reports as a JSON array, with their checks from
+/// [`crate::answers::check_reports`] if there are any
+pub fn print_json(reports: &[Report], checks: Option<&[[Check; 2]]>) {
+    let reports: Vec<Value> = reports
+        .iter()
+        .enumerate()
+        .map(|(i, report)| report_json(report, checks.map(|c| &c[i])))
+        .collect();
+    println!("{:#}", Value::Array(reports));
+}
+
+/// Escape tabs, newlines, and backslashes, so any text fits in one field
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Undo [`escape`]
+pub fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+pub const TSV_HEADER: &str = "day\tinput\tpart\tstatus\tanswer\tparse_ns\tpart_ns\tcheck\texpected";
+
+/// A row for each part of a report, with the median times, and the checks if
+/// they're given. Answers and errors share a column, with tabs and newlines
+/// escaped as `\t` and `\n`, and so do expected answers and check errors.
+pub fn report_tsv(report: &Report, checks: Option<&[Check; 2]>) -> Vec<String> {
+    let nanos = |s: &Samples| {
+        s.median()
+            .map_or(String::new(), |d| d.as_nanos().to_string())
+    };
+    parts(report, checks)
+        .into_iter()
+        .map(|(part, outcome, samples, check)| {
+            let text = match outcome {
+                Outcome::Answer(answer) => answer.to_string(),
+                _ => error(outcome).unwrap_or_default(),
+            };
+            let (status, expected) = match check {
+                Some(Check::Error(e)) => ("error", e.clone()),
+                Some(check) => (check.status(), expected(check).unwrap_or_default()),
+                None => ("", String::new()),
+            };
+            [
+                report.day.to_string(),
+                escape(&report.input_name()),
+                part.to_string(),
+                outcome.status().to_owned(),
+                escape(&text),
+                nanos(&report.timings.parse),
+                nanos(samples),
+                status.to_owned(),
+                escape(&expected),
+            ]
+            .join("\t")
+        })
+        .collect()
+}
+
+/// Print the reports as TSV, with their checks from
+/// [`crate::answers::check_reports`] if there are any
+pub fn print_tsv(reports: &[Report], checks: Option<&[[Check; 2]]>) {
+    println!("{TSV_HEADER}");
+    for (i, report) in reports.iter().enumerate() {
+        for row in report_tsv(report, checks.map(|c| &c[i])) {
+            println!("{row}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use test_log::test;
+
+    use super::*;
+    use crate::{problems::Answer, runner::Timings};
+
+    fn report() -> Report {
+        Report {
+            day: 10,
+            input: PathBuf::from("inputs/day10.txt"),
+            parsed: true,
+            part_one: Outcome::Answer(Answer::Int(13140)),
+            part_two: Outcome::Answer(Answer::grid("##..\n#..#")),
+            timings: Timings::default(),
+        }
+    }
+
+    #[test]
+    fn test_json() {
+        let value = report_json(&report(), None);
+        assert_eq!(value["day"], 10);
+        assert_eq!(value["input"], "inputs/day10.txt");
+        assert_eq!(value["parts"][0]["status"], "ok");
+        assert_eq!(value["parts"][0]["answer"], "13140");
+        assert_eq!(value["parts"][1]["answer"], "##..\n#..#");
+        assert_eq!(value["parts"][1]["time"]["runs"], 0);
+        assert_eq!(value["parts"][1]["time"]["median_ns"], Value::Null);
+
+        // Multi-line answers come back out the same
+        let text = value.to_string();
+        let parsed: Value = serde_json::from_str(&text).unwrap();
+        let answer = Answer::from_str(parsed["parts"][1]["answer"].as_str().unwrap());
+        assert_eq!(answer, Ok(Answer::grid("##..\n#..#")));
+
+        let mut stub = report();
+        stub.part_two = Outcome::Stub;
+        stub.part_one = Outcome::Error("Bad\tinput".to_owned());
+        let value = report_json(&stub, None);
+        assert_eq!(value["parts"][0]["status"], "error");
+        assert_eq!(value["parts"][0]["error"], "Bad\tinput");
+        assert_eq!(value["parts"][1]["status"], "unimplemented");
+        assert_eq!(value["parts"][1]["answer"], Value::Null);
+
+        stub.part_two = Outcome::Timeout(std::time::Duration::from_secs(2));
+        let value = report_json(&stub, None);
+        assert_eq!(value["parts"][1]["status"], "timeout");
+        assert_eq!(value["parts"][1]["error"], "Timed out after 2.0s");
+        assert_eq!(value["parts"][1]["check"], Value::Null);
+
+        let checks = [
+            Check::Pass,
+            Check::Fail {
+                expected: Answer::grid("#..#\n##.."),
+            },
+        ];
+        let value = report_json(&report(), Some(&checks));
+        assert_eq!(value["parts"][0]["check"]["status"], "pass");
+        assert_eq!(value["parts"][0]["check"]["expected"], Value::Null);
+        assert_eq!(value["parts"][1]["check"]["status"], "fail");
+        assert_eq!(value["parts"][1]["check"]["expected"], "#..#\n##..");
+    }
+
+    #[test]
+    fn test_tsv() {
+        let rows = report_tsv(&report(), None);
+        assert_eq!(
+            rows,
+            vec![
+                "10\tinputs/day10.txt\t1\tok\t13140\t\t\t\t",
+                "10\tinputs/day10.txt\t2\tok\t##..\\n#..#\t\t\t\t",
+            ]
+        );
+
+        let checks = [
+            Check::Fail {
+                expected: Answer::Int(15260),
+            },
+            Check::Error("Bad\nanswers".to_owned()),
+        ];
+        let rows = report_tsv(&report(), Some(&checks));
+        assert_eq!(
+            rows,
+            vec![
+                "10\tinputs/day10.txt\t1\tok\t13140\t\t\tfail\t15260",
+                "10\tinputs/day10.txt\t2\tok\t##..\\n#..#\t\t\terror\tBad\\nanswers",
+            ]
+        );
+        assert_eq!(escape("a\\b\tc\n"), "a\\\\b\\tc\\n");
+        assert_eq!(unescape(&escape("a\\b\tc\r\n")), "a\\b\tc\r\n");
+    }
+}
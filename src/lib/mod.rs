@@ -1,3 +1,5 @@
 pub mod positions;
+pub mod voxels;
 
 pub use positions::{Compass, Position, Turn};
+pub use voxels::{Voxel, VoxelSet};
@@ -0,0 +1,213 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::ops::{self, RangeInclusive};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use crate::positions::Value;
+
+/// 3D positions, in the form (x, y, z)
+///
+/// ```
+/// use adventofcode2022::voxels::Voxel;
+///
+/// let v: Voxel = "1,2,3".parse().unwrap();
+/// assert_eq!(v, Voxel(1, 2, 3));
+/// assert_eq!(v + (1, 0, -1), Voxel(2, 2, 2));
+/// assert!(v.neighbors().contains(&Voxel(1, 2, 4)));
+/// ```
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Voxel(pub Value, pub Value, pub Value);
+
+impl Voxel {
+    /// The 6 face-adjacent voxels
+    pub fn neighbors(self) -> [Voxel; 6] {
+        [
+            self + (1, 0, 0),
+            self + (-1, 0, 0),
+            self + (0, 1, 0),
+            self + (0, -1, 0),
+            self + (0, 0, 1),
+            self + (0, 0, -1),
+        ]
+    }
+}
+
+impl fmt::Display for Voxel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.0, self.1, self.2)
+    }
+}
+
+impl From<(Value, Value, Value)> for Voxel {
+    fn from((x, y, z): (Value, Value, Value)) -> Self {
+        Voxel(x, y, z)
+    }
+}
+
+impl FromStr for Voxel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split(',');
+        let mut next = || -> anyhow::Result<Value> {
+            let p = parts
+                .next()
+                .ok_or_else(|| anyhow!("Expected 3 coordinates in '{s}'"))?;
+            Ok(p.trim().parse()?)
+        };
+        let v = Voxel(next()?, next()?, next()?);
+        if parts.next().is_some() {
+            return Err(anyhow!("Expected 3 coordinates in '{s}'"));
+        }
+        Ok(v)
+    }
+}
+
+impl ops::Add<(Value, Value, Value)> for Voxel {
+    type Output = Self;
+
+    fn add(self, (dx, dy, dz): (Value, Value, Value)) -> Self {
+        let Voxel(x, y, z) = self;
+        Voxel(x + dx, y + dy, z + dz)
+    }
+}
+
+impl ops::Sub<Voxel> for Voxel {
+    type Output = (Value, Value, Value);
+
+    fn sub(self, Voxel(x2, y2, z2): Voxel) -> Self::Output {
+        let Voxel(x1, y1, z1) = self;
+        (x1 - x2, y1 - y2, z1 - z2)
+    }
+}
+
+/// A set of filled unit cubes
+///
+/// ```
+/// use adventofcode2022::voxels::{Voxel, VoxelSet};
+///
+/// let set: VoxelSet = [Voxel(1, 1, 1), Voxel(2, 1, 1)].into_iter().collect();
+/// assert_eq!(set.surface_area(), 10);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct VoxelSet(HashSet<Voxel>);
+
+impl VoxelSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, v: Voxel) -> bool {
+        self.0.contains(&v)
+    }
+
+    pub fn insert(&mut self, v: Voxel) -> bool {
+        self.0.insert(v)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Voxel> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// The smallest box containing every voxel, as (x, y, z) ranges
+    pub fn bounds(&self) -> Option<[RangeInclusive<Value>; 3]> {
+        let first = self.0.iter().next()?;
+        let (mut lo, mut hi) = (*first, *first);
+        for &Voxel(x, y, z) in &self.0 {
+            lo = Voxel(lo.0.min(x), lo.1.min(y), lo.2.min(z));
+            hi = Voxel(hi.0.max(x), hi.1.max(y), hi.2.max(z));
+        }
+        Some([lo.0..=hi.0, lo.1..=hi.1, lo.2..=hi.2])
+    }
+
+    /// Number of faces not touching another voxel in the set
+    pub fn surface_area(&self) -> usize {
+        self.iter()
+            .flat_map(Voxel::neighbors)
+            .filter(|&n| !self.contains(n))
+            .count()
+    }
+
+    /// Every empty voxel reachable from outside, within a box one larger than
+    /// the bounds on every side
+    pub fn exterior(&self) -> VoxelSet {
+        let Some([xs, ys, zs]) = self.bounds() else {
+            return VoxelSet::new();
+        };
+        let (xs, ys, zs) = (
+            xs.start() - 1..=xs.end() + 1,
+            ys.start() - 1..=ys.end() + 1,
+            zs.start() - 1..=zs.end() + 1,
+        );
+        let in_box = |Voxel(x, y, z): Voxel| xs.contains(&x) && ys.contains(&y) && zs.contains(&z);
+
+        let start = Voxel(*xs.start(), *ys.start(), *zs.start());
+        let mut outside = VoxelSet::new();
+        outside.insert(start);
+        let mut queue = VecDeque::from([start]);
+        while let Some(v) = queue.pop_front() {
+            for n in v.neighbors() {
+                if in_box(n) && !self.contains(n) && outside.insert(n) {
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        outside
+    }
+
+    /// Number of faces that can be reached from outside, ignoring any
+    /// internal air pockets
+    pub fn exterior_surface_area(&self) -> usize {
+        let outside = self.exterior();
+        self.iter()
+            .flat_map(Voxel::neighbors)
+            .filter(|&n| outside.contains(n))
+            .count()
+    }
+}
+
+impl FromIterator<Voxel> for VoxelSet {
+    fn from_iter<T: IntoIterator<Item = Voxel>>(iter: T) -> Self {
+        VoxelSet(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("2,-3,5".parse::<Voxel>().unwrap(), Voxel(2, -3, 5));
+        assert!("2,3".parse::<Voxel>().is_err());
+        assert!("2,3,4,5".parse::<Voxel>().is_err());
+    }
+
+    #[test]
+    fn test_hollow_cube() {
+        // A 3x3x3 cube with the center missing
+        let set: VoxelSet = (0..27)
+            .map(|n| Voxel(n % 3, (n / 3) % 3, n / 9))
+            .filter(|&v| v != Voxel(1, 1, 1))
+            .collect();
+
+        assert_eq!(set.len(), 26);
+        assert_eq!(set.bounds(), Some([0..=2, 0..=2, 0..=2]));
+        assert_eq!(set.surface_area(), 54 + 6);
+        assert_eq!(set.exterior_surface_area(), 54);
+        assert!(!set.exterior().contains(Voxel(1, 1, 1)));
+    }
+}
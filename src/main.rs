@@ -1,32 +1,420 @@
+mod answers;
+mod client;
+mod format;
 mod problems;
+mod runner;
+mod scaffold;
+mod submit;
 
-use std::path::PathBuf;
+use std::{path::PathBuf, process::ExitCode, str::FromStr, time::Duration};
 
-use anyhow::Context;
-use clap::Parser;
+use anyhow::{anyhow, bail};
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand};
 
-use problems::solver;
+use format::Format;
+use problems::{registry, DAYS};
+use runner::{Outcome, Parts};
+
+// Exit statuses, so scripts can tell the ways of failing apart. clap already
+// uses 2 for bad arguments.
+const EXIT_ERROR: u8 = 1;
+const EXIT_PANIC: u8 = 3;
+const EXIT_MISMATCH: u8 = 4;
+
+/// Which days to run
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Target {
+    Day(usize),
+    All,
+}
+
+impl FromStr for Target {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(Target::All);
+        }
+        let day = s
+            .parse()
+            .map_err(|_| anyhow!("Expected a day number or 'all', got '{s}'"))?;
+        Ok(Target::Day(check_day(day)?))
+    }
+}
+
+fn check_day(day: usize) -> anyhow::Result<usize> {
+    if !DAYS.contains(&day) {
+        bail!(
+            "There is no day {day}, expected {}-{}",
+            DAYS.start(),
+            DAYS.end()
+        );
+    }
+    Ok(day)
+}
+
+fn parse_day(s: &str) -> anyhow::Result<usize> {
+    check_day(
+        s.parse()
+            .map_err(|_| anyhow!("Expected a day number, got '{s}'"))?,
+    )
+}
+
+fn parse_timeout(s: &str) -> anyhow::Result<Duration> {
+    let secs: f64 = s
+        .parse()
+        .map_err(|_| anyhow!("Expected a number of seconds, got '{s}'"))?;
+    if !(secs > 0.0 && secs.is_finite()) {
+        bail!("The timeout must be more than 0 seconds, got '{s}'");
+    }
+    Ok(Duration::from_secs_f64(secs))
+}
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
-    day: usize,
+    #[clap(subcommand)]
+    command: Option<Command>,
 
+    #[clap(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Show which days and parts are done, and which have inputs
+    List,
+
+    /// Download a day's input, unless it's already in inputs/
+    Fetch {
+        #[clap(value_parser = parse_day)]
+        day: usize,
+    },
+
+    /// Submit the answer to one part of a day, and record how it went in
+    /// answers/submissions.tsv
+    Submit {
+        #[clap(value_parser = parse_day)]
+        day: usize,
+
+        /// Which part to submit, 1 or 2
+        #[clap(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+    },
+
+    /// Start a new day from the template, with empty input and example files
+    New {
+        #[clap(value_parser = parse_day)]
+        day: usize,
+
+        /// The puzzle's title
+        #[clap(long)]
+        title: Option<String>,
+
+        /// Replace the day if it's still an unimplemented stub
+        #[clap(long)]
+        force: bool,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+struct RunArgs {
+    /// The day to run, or 'all' for every day with an input
+    #[clap(required = true, value_parser = clap::value_parser!(Target))]
+    day: Option<Target>,
+
+    /// Read the input from this file rather than inputs/dayNN.txt, or from
+    /// stdin for '-'. Give it more than once to run on each input in turn.
     #[clap(short, long, value_parser)]
-    input: Option<PathBuf>,
+    input: Vec<PathBuf>,
+
+    /// Which part to run: 1, 2, or both. The other one isn't run at all.
+    #[clap(long, default_value = "both", value_parser = clap::value_parser!(Parts))]
+    part: Parts,
+
+    /// Report how long parsing and each part took
+    #[clap(long)]
+    time: bool,
+
+    /// Run each phase N times, and report the min, median, and max times
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    bench: Option<u32>,
+
+    /// Give up on parsing or a part if it takes longer than this many
+    /// seconds, including all of its --bench runs. What's given up on can't be
+    /// stopped, so it keeps running alongside everything after it: with
+    /// 'all', this is best-effort, and later timings are unreliable.
+    #[clap(long, value_name = "SECS", value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+
+    /// Compare the answers to those stored in answers/dayNN.txt
+    #[clap(long)]
+    check: bool,
+
+    /// How to print the results: text, json, or tsv
+    #[clap(long, default_value = "text", value_parser = clap::value_parser!(Format))]
+    format: Format,
+}
+
+impl RunArgs {
+    /// How many times to run each phase
+    fn runs(&self) -> usize {
+        self.bench.unwrap_or(1) as usize
+    }
+
+    fn show_timings(&self) -> bool {
+        self.time || self.bench.is_some()
+    }
+}
+
+fn input_path(day: usize) -> PathBuf {
+    format!("inputs/day{:02}.txt", day).into()
+}
+
+/// Print an overview of the calendar: which days are registered, which of
+/// their parts are done, and which have inputs
+fn list() -> ExitCode {
+    let (mut done, mut inputs) = (0, 0);
+    let rows: Vec<Vec<String>> = DAYS
+        .map(|day| {
+            let has_input = client::is_cached(&input_path(day));
+            inputs += has_input as usize;
+            let (parts, title) = match registry::lookup(day) {
+                Some(r) => {
+                    done += r.parts.iter().filter(|&&done| done).count();
+                    let parts = r.parts.map(|done| if done { '*' } else { '.' });
+                    (parts.iter().collect(), r.title)
+                }
+                None => ("  ".to_owned(), ""),
+            };
+            let input = if has_input { "yes" } else { "-" };
+            vec![format!("{day}"), parts, input.to_owned(), title.to_owned()]
+        })
+        .collect();
+
+    runner::print_columns(&["Day", "Parts", "Input", "Title"], &rows, &[0]);
+    let total = 2 * DAYS.count();
+    println!("\n{done} of {total} parts done, {inputs} inputs");
+    ExitCode::SUCCESS
 }
 
-fn main() {
+fn main() -> ExitCode {
     env_logger::init();
     let args = Args::parse();
+    if args.run.day == Some(Target::All) && !args.run.input.is_empty() {
+        Args::command()
+            .bin_name("solve")
+            .error(
+                ErrorKind::ArgumentConflict,
+                "'--input' can't be used with 'all', which runs each day on its own input",
+            )
+            .exit();
+    }
+
+    match args.command {
+        Some(Command::List) => list(),
+        Some(Command::New { day, title, force }) => {
+            let title = title.unwrap_or_else(|| format!("Day {day}"));
+            match scaffold::new_day(day, &title, &input_path(day), force) {
+                Ok(changed) => {
+                    for path in changed {
+                        println!("Wrote {}", path.display());
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("Error: {e:#}");
+                    ExitCode::from(EXIT_ERROR)
+                }
+            }
+        }
+        Some(Command::Fetch { day }) => {
+            let path = input_path(day);
+            let fetched = client::Client::from_env()
+                .and_then(|client| client::fetch_input(&client, day, &path));
+            match fetched {
+                Ok(true) => println!("Wrote {}", path.display()),
+                Ok(false) => println!("Already have {}", path.display()),
+                Err(e) => {
+                    eprintln!("Error: {e:#}");
+                    return ExitCode::from(EXIT_ERROR);
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Some(Command::Submit { day, part }) => submit(day, part as usize),
+        None => run(&args.run),
+    }
+}
+
+/// Solve one part of a day, and submit the answer unless the ledger says it's
+/// already known how that would go
+fn submit(day: usize, part: usize) -> ExitCode {
+    std::panic::set_hook(Box::new(|info| log::debug!("{info}")));
+
+    fetch_missing([day]);
+    let path = input_path(day);
+    let report = runner::run_day(day, &path, Parts::only(part), 1, None);
+    let outcome = if part == 1 {
+        report.part_one
+    } else {
+        report.part_two
+    };
+    let answer = match outcome {
+        Outcome::Answer(answer) => answer,
+        Outcome::NoInput => {
+            eprintln!("Error: No input for day {day} in {}", path.display());
+            return ExitCode::from(EXIT_ERROR);
+        }
+        outcome => {
+            eprintln!("Error: Nothing to submit: {outcome}");
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+
+    println!("Day {day} part {part}: submitting {answer}");
+    let verdict = client::Client::from_env().and_then(|client| {
+        submit::submit(&client, submit::LEDGER_PATH.as_ref(), day, part, &answer)
+    });
+    match verdict {
+        Ok(verdict) => {
+            println!("{verdict}");
+            match verdict {
+                submit::Verdict::Correct | submit::Verdict::AlreadySolved => ExitCode::SUCCESS,
+                v if v.is_wrong() => ExitCode::from(EXIT_MISMATCH),
+                _ => ExitCode::from(EXIT_ERROR),
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+/// Download the default input for each of `days` that doesn't have one yet,
+/// warning about anything that goes wrong rather than stopping
+fn fetch_missing(days: impl IntoIterator<Item = usize>) {
+    let missing: Vec<usize> = days
+        .into_iter()
+        .filter(|&day| !client::is_cached(&input_path(day)))
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+
+    let client = match client::Client::from_env() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Not fetching missing inputs: {e:#}");
+            return;
+        }
+    };
+    for day in missing {
+        eprintln!("Fetching the input for day {day}");
+        if let Err(e) = client::fetch_input(&client, day, &input_path(day)) {
+            eprintln!("Couldn't fetch the input for day {day}: {e:#}");
+        }
+    }
+}
+
+fn run(args: &RunArgs) -> ExitCode {
+    // Panics are caught and reported with everything else, so keep the
+    // default message and backtrace out of the output
+    std::panic::set_hook(Box::new(|info| log::debug!("{info}")));
+
+    let target = args.day.expect("clap requires a day without a subcommand");
+    // Anything else gets printed all at once at the end
+    let text = args.format == Format::Text;
+    let mut missing = false;
+    let reports = match target {
+        Target::Day(day) => {
+            let inputs = if args.input.is_empty() {
+                fetch_missing([day]);
+                vec![input_path(day)]
+            } else {
+                args.input.clone()
+            };
+            // With more than one input, each gets its own labeled block
+            let labeled = inputs.len() > 1;
+
+            let mut reports = Vec::new();
+            for (i, path) in inputs.iter().enumerate() {
+                let report = runner::run_day(day, path, args.part, args.runs(), args.timeout);
+                if !text {
+                    missing |= !report.has_input();
+                    reports.push(report);
+                    continue;
+                }
+                if labeled {
+                    if i > 0 {
+                        println!();
+                    }
+                    println!("== {} ==", report.input_name());
+                }
+                match report.input_outcome() {
+                    None => runner::print_parts(&report),
+                    Some(Outcome::NoInput) => {
+                        eprintln!("Error: No input for day {day} in {}", report.input_name());
+                        missing = true;
+                    }
+                    Some(Outcome::Error(e)) => eprintln!("Error: {e}"),
+                    Some(outcome) => eprintln!("Error: {outcome}"),
+                }
+                if args.show_timings() && report.parsed {
+                    println!();
+                    runner::print_timings(std::slice::from_ref(&report));
+                }
+                reports.push(report);
+            }
+            reports
+        }
+        Target::All => {
+            fetch_missing(DAYS);
+            let reports: Vec<_> = DAYS
+                .map(|day| {
+                    runner::run_day(day, &input_path(day), args.part, args.runs(), args.timeout)
+                })
+                .collect();
+            if text {
+                runner::print_table(&reports);
+                if args.show_timings() {
+                    println!();
+                    runner::print_timings(&reports);
+                }
+            }
+            reports
+        }
+    };
 
-    let path = args
-        .input
-        .unwrap_or_else(|| format!("inputs/day{:02}.txt", args.day).into());
+    if reports.iter().any(|r| r.timed_out()) {
+        eprintln!(
+            "Warning: what timed out is still running in the background, so the timings \
+             after it are unreliable"
+        );
+    }
 
-    let input = std::fs::File::open(path).context("Opening file").unwrap();
+    let checks = args.check.then(|| answers::check_reports(&reports));
+    match args.format {
+        Format::Text => {
+            if let Some(checks) = &checks {
+                println!();
+                answers::print_checks(&reports, checks);
+            }
+        }
+        Format::Json => format::print_json(&reports, checks.as_deref()),
+        Format::Tsv => format::print_tsv(&reports, checks.as_deref()),
+    }
+    let mismatched = checks.is_some_and(|checks| !answers::all_pass(&checks));
 
-    let solution = solver(args.day, input).unwrap();
-    println!("Part one: {}", solution.part_one());
-    println!("Part two: {}", solution.part_two());
+    if reports.iter().any(|r| r.is_panic()) {
+        ExitCode::from(EXIT_PANIC)
+    } else if missing || reports.iter().any(|r| r.is_failure()) {
+        ExitCode::from(EXIT_ERROR)
+    } else if mismatched {
+        ExitCode::from(EXIT_MISMATCH)
+    } else {
+        ExitCode::SUCCESS
+    }
 }
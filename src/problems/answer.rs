@@ -0,0 +1,133 @@
+use std::{
+    convert::Infallible,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+/// The answer to one part of a day's puzzle.
+///
+/// Answers are equal when they're written the same way, whatever the variant,
+/// so a digit-only `Text` like a SNAFU number matches the `Int` it parses as.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+    /// A picture, like the CRT screen on day 10, one string per row
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    /// A grid answer from text with one row per line
+    pub fn grid(s: &str) -> Self {
+        Answer::Grid(s.lines().map(str::to_owned).collect())
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Grid(rows) if rows.len() > 1)
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Int(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i32, i64, i128, isize, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_owned())
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    /// Integers become `Int`, several lines become a `Grid`, and anything else
+    /// is `Text`, so that answers compare by value rather than formatting
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_matches('\n');
+        if s.contains('\n') {
+            return Ok(Answer::grid(s));
+        }
+        let s = s.trim();
+        Ok(match s.parse::<i128>() {
+            Ok(n) => Answer::Int(n),
+            Err(_) => Answer::Text(s.to_owned()),
+        })
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Int(a), Answer::Int(b)) => a == b,
+            (Answer::Grid(a), Answer::Grid(b)) => a == b,
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl Hash for Answer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_string().hash(state);
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{n}"),
+            Answer::Text(s) => f.write_str(s),
+            Answer::Grid(rows) => f.write_str(&rows.join("\n")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let parse = |s: &str| s.parse::<Answer>().unwrap();
+        assert_eq!(parse("24000"), Answer::Int(24000));
+        assert_eq!(parse(" -3 \n"), Answer::Int(-3));
+        assert_eq!(parse("CMZ"), Answer::from("CMZ"));
+        assert_eq!(parse("2=-1=0"), Answer::from("2=-1=0"));
+
+        let grid = parse("\n##..\n#..#\n");
+        assert_eq!(grid, Answer::Grid(vec!["##..".into(), "#..#".into()]));
+        assert!(grid.is_multiline());
+        assert_eq!(grid.to_string(), "##..\n#..#");
+        assert_eq!(parse(&grid.to_string()), grid);
+    }
+
+    #[test]
+    fn test_eq() {
+        // A SNAFU number with no '-' or '=' is read back as an integer, but
+        // still matches the text the solver gave
+        let snafu = Answer::from("1121");
+        assert_eq!("1121".parse::<Answer>().unwrap(), snafu);
+        assert_eq!(Answer::Int(1121), snafu);
+        assert_ne!(Answer::Int(1122), snafu);
+        assert_ne!(Answer::from("2=-1=0"), Answer::Int(2));
+        assert_eq!(Answer::grid("##"), Answer::from("##"));
+    }
+}
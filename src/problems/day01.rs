@@ -4,7 +4,7 @@ use std::{
     io::{BufRead, BufReader, Read},
 };
 
-use super::Solver;
+use super::{registry::register, Answer, Solver};
 
 pub struct Elves {
     calories: Vec<Vec<i64>>,
@@ -54,17 +54,19 @@ impl Elves {
 
 pub struct Day01(Elves);
 
+register!(1, Day01, "Calorie Counting");
+
 impl Solver for Day01 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
         Ok(Day01(Elves::read(input)?))
     }
 
-    fn part_one(&self) -> String {
-        self.0.sums().max().unwrap_or_default().to_string()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        Ok(self.0.sums().max().unwrap_or_default().into())
     }
 
-    fn part_two(&self) -> String {
-        self.0.maxes(3).iter().sum::<i64>().to_string()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        Ok(self.0.maxes(3).iter().sum::<i64>().into())
     }
 }
 
@@ -109,10 +111,10 @@ mod tests {
     pub fn solving() {
         let solver = Day01::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
 
-        let out = solver.part_one();
-        assert_eq!(out, "24000");
+        let out = solver.part_one().unwrap();
+        assert_eq!(out, Answer::Int(24000));
 
-        let out = solver.part_two();
-        assert_eq!(out, "45000");
+        let out = solver.part_two().unwrap();
+        assert_eq!(out, Answer::Int(45000));
     }
 }
@@ -3,7 +3,7 @@ use log::debug;
 
 use std::{io::Read, str::FromStr};
 
-use super::{parse_lines, Solver};
+use super::{parse_lines, registry::register, Answer, Solver};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Hand {
@@ -126,6 +126,8 @@ impl FromStr for Pair {
 
 pub struct Day02(Vec<Pair>);
 
+register!(2, Day02, "Rock Paper Scissors");
+
 impl Solver for Day02 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
         let pairs = parse_lines::<Pair>(input)?;
@@ -133,20 +135,22 @@ impl Solver for Day02 {
         Ok(Day02(pairs))
     }
 
-    fn part_one(&self) -> String {
-        self.0
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let score = self
+            .0
             .iter()
             .map(|p| p.assume_simple().score())
-            .sum::<i64>()
-            .to_string()
+            .sum::<i64>();
+        Ok(score.into())
     }
 
-    fn part_two(&self) -> String {
-        self.0
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let score = self
+            .0
             .iter()
             .map(|p| p.assume_solution().score())
-            .sum::<i64>()
-            .to_string()
+            .sum::<i64>();
+        Ok(score.into())
     }
 }
 
@@ -172,7 +176,7 @@ mod tests {
         assert_eq!(pairs[1].assume_simple().score(), 1);
         assert_eq!(pairs[2].assume_simple().score(), 6);
 
-        assert_eq!(solver.part_one(), "15");
+        assert_eq!(solver.part_one().unwrap(), Answer::Int(15));
     }
 
     #[test]
@@ -185,6 +189,6 @@ mod tests {
         assert_eq!(pairs[1].assume_solution().score(), 1);
         assert_eq!(pairs[2].assume_solution().score(), 7);
 
-        assert_eq!(solver.part_two(), "12");
+        assert_eq!(solver.part_two().unwrap(), Answer::Int(12));
     }
 }
@@ -5,7 +5,9 @@ use std::{
     str::FromStr,
 };
 
-use super::{solutions::parse_lines, Solver};
+use anyhow::anyhow;
+
+use super::{registry::register, solutions::parse_lines, Answer, Solver};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Item(char);
@@ -82,6 +84,8 @@ impl FromStr for Rucksack {
 
 pub struct Day03(Vec<Rucksack>);
 
+register!(3, Day03, "Rucksack Reorganization");
+
 impl Day03 {
     pub fn find_common<'r, I: Iterator<Item = &'r Rucksack>>(group: I) -> Option<Item> {
         let mut counts = HashMap::new();
@@ -110,10 +114,11 @@ impl Day03 {
         None
     }
 
-    pub fn badge_priorities(&self) -> impl Iterator<Item = i64> + '_ {
-        self.0.as_slice().chunks(3).map(|group| {
-            let c = Self::find_common(group.iter()).unwrap();
-            c.priority()
+    pub fn badge_priorities(&self) -> impl Iterator<Item = anyhow::Result<i64>> + '_ {
+        self.0.as_slice().chunks(3).enumerate().map(|(i, group)| {
+            let c = Self::find_common(group.iter())
+                .ok_or_else(|| anyhow!("No badge common to all of group {}", i + 1))?;
+            Ok(c.priority())
         })
     }
 }
@@ -125,19 +130,21 @@ impl Solver for Day03 {
         Ok(Day03(rucksacks))
     }
 
-    fn part_one(&self) -> String {
+    fn part_one(&self) -> anyhow::Result<Answer> {
         let mut total = 0;
-        for r in &self.0 {
-            let c = r.find_duplicate().unwrap();
+        for (i, r) in self.0.iter().enumerate() {
+            let c = r
+                .find_duplicate()
+                .ok_or_else(|| anyhow!("No item in both compartments of rucksack {}", i + 1))?;
             total += c.priority();
         }
 
-        format!("{}", total)
+        Ok(total.into())
     }
 
-    fn part_two(&self) -> String {
-        let psum = self.badge_priorities().sum::<i64>();
-        format!("{}", psum)
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let psum = self.badge_priorities().sum::<anyhow::Result<i64>>()?;
+        Ok(psum.into())
     }
 }
 
@@ -168,15 +175,18 @@ mod tests {
             assert_eq!(r.find_duplicate().unwrap().priority(), s);
         }
 
-        let s = solver.part_one();
-        assert_eq!(&s, "157");
+        let s = solver.part_one().unwrap();
+        assert_eq!(s, Answer::Int(157));
     }
 
     #[test]
     pub fn groups() {
         let solver = Day03::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        let priorities = solver.badge_priorities().collect::<Vec<i64>>();
+        let priorities = solver
+            .badge_priorities()
+            .collect::<anyhow::Result<Vec<i64>>>()
+            .unwrap();
         assert_eq!(priorities, vec![18, 52]);
-        assert_eq!(solver.badge_priorities().sum::<i64>(), 70);
+        assert_eq!(solver.part_two().unwrap(), Answer::Int(70));
     }
 }
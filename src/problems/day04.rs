@@ -1,6 +1,6 @@
 use std::{io::Read, ops::RangeInclusive, str::FromStr};
 
-use super::{solutions::parse_lines, Solver};
+use super::{registry::register, solutions::parse_lines, Answer, Solver};
 
 pub type Assignment = RangeInclusive<i64>;
 
@@ -47,6 +47,8 @@ impl ElfPair {
 
 pub struct Day04(Vec<ElfPair>);
 
+register!(4, Day04, "Camp Cleanup");
+
 impl Day04 {
     pub fn contained_pairs(&self) -> usize {
         self.0.iter().filter(|&p| p.fully_contained()).count()
@@ -64,12 +66,12 @@ impl Solver for Day04 {
         Ok(Day04(items))
     }
 
-    fn part_one(&self) -> String {
-        format!("{}", self.contained_pairs())
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        Ok(self.contained_pairs().into())
     }
 
-    fn part_two(&self) -> String {
-        format!("{}", self.overlapping_pairs())
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        Ok(self.overlapping_pairs().into())
     }
 }
 
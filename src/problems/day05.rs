@@ -2,7 +2,7 @@ use std::{io::Read, str::FromStr};
 
 use anyhow::bail;
 
-use super::Solver;
+use super::{registry::register, Answer, Solver};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Crate(char);
@@ -203,6 +203,8 @@ impl Stack {
 
 pub struct Day05(Stack);
 
+register!(5, Day05, "Supply Stacks");
+
 impl Solver for Day05 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
         let mut buf = String::new();
@@ -213,16 +215,16 @@ impl Solver for Day05 {
         Ok(Day05(stack))
     }
 
-    fn part_one(&self) -> String {
+    fn part_one(&self) -> anyhow::Result<Answer> {
         let mut stack = self.0.clone();
         stack.apply_all();
-        stack.tops()
+        Ok(stack.tops().into())
     }
 
-    fn part_two(&self) -> String {
+    fn part_two(&self) -> anyhow::Result<Answer> {
         let mut stack = self.0.clone();
         stack.apply_all_multiple();
-        stack.tops()
+        Ok(stack.tops().into())
     }
 }
 
@@ -2,7 +2,7 @@ use std::{collections::VecDeque, io::Read, str::FromStr};
 
 use log::{debug, info};
 
-use super::Solver;
+use super::{registry::register, Answer, Solver};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct Message(String);
@@ -46,6 +46,8 @@ impl Message {
 
 pub struct Day06(Message);
 
+register!(6, Day06, "Tuning Trouble");
+
 impl Solver for Day06 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
         let mut input = input;
@@ -55,14 +57,14 @@ impl Solver for Day06 {
         Ok(Day06(Message(buf.trim().to_owned())))
     }
 
-    fn part_one(&self) -> String {
+    fn part_one(&self) -> anyhow::Result<Answer> {
         let ix = self.0.find_no_repeats(4).unwrap_or(self.0 .0.len());
-        format!("{ix}")
+        Ok(ix.into())
     }
 
-    fn part_two(&self) -> String {
+    fn part_two(&self) -> anyhow::Result<Answer> {
         let ix = self.0.find_no_repeats(14).unwrap_or(self.0 .0.len());
-        format!("{ix}")
+        Ok(ix.into())
     }
 }
 
@@ -6,9 +6,9 @@ use std::{
     str::FromStr,
 };
 
-use anyhow::{bail, Context};
+use anyhow::{anyhow, bail, Context};
 
-use super::Solver;
+use super::{registry::register, Answer, Solver};
 
 const SMALL_FILE_THRESHOLD: i64 = 100_000;
 const FILE_SYSTEM_SIZE: i64 = 70_000_000;
@@ -249,6 +249,8 @@ impl Filesystem {
 
 pub struct Day07(Filesystem);
 
+register!(7, Day07, "No Space Left On Device");
+
 impl Day07 {
     pub fn small_dir_sum(&self, cutoff: i64) -> i64 {
         let sizes = self.0.total_sizes();
@@ -310,14 +312,16 @@ impl Solver for Day07 {
         Ok(Day07(fs))
     }
 
-    fn part_one(&self) -> String {
+    fn part_one(&self) -> anyhow::Result<Answer> {
         let sum = self.small_dir_sum(SMALL_FILE_THRESHOLD);
-        format!("{sum}")
+        Ok(sum.into())
     }
 
-    fn part_two(&self) -> String {
-        let (size, _p) = self.free_below(MAX_SIZE).unwrap();
-        format!("{size}")
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let (size, _p) = self
+            .free_below(MAX_SIZE)
+            .ok_or_else(|| anyhow!("No directory frees enough space"))?;
+        Ok(size.into())
     }
 }
 
@@ -2,7 +2,7 @@ use std::{collections::HashSet, io::Read, panic, str::FromStr};
 
 use anyhow::bail;
 
-use super::Solver;
+use super::{registry::register, Answer, Solver};
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Grid {
@@ -168,6 +168,8 @@ impl Grid {
 
 pub struct Day08(Grid);
 
+register!(8, Day08, "Treetop Tree House");
+
 impl Day08 {
     pub fn visible_count(&self) -> usize {
         self.0.visible().len()
@@ -184,14 +186,14 @@ impl Solver for Day08 {
         Ok(Day08(grid))
     }
 
-    fn part_one(&self) -> String {
+    fn part_one(&self) -> anyhow::Result<Answer> {
         let vis = self.visible_count();
-        format!("{vis}")
+        Ok(vis.into())
     }
 
-    fn part_two(&self) -> String {
+    fn part_two(&self) -> anyhow::Result<Answer> {
         let (_r, _c, s) = self.0.most_scenic();
-        format!("{s}")
+        Ok(s.into())
     }
 }
 
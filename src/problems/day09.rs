@@ -3,7 +3,7 @@ use std::{collections::HashSet, io::Read, str::FromStr};
 use adventofcode2022::{Compass, Position};
 use anyhow::{anyhow, bail};
 
-use super::{solutions::parse_from_read, Solver};
+use super::{registry::register, solutions::parse_from_read, Answer, Solver};
 
 pub struct Instructions(Vec<(Compass, usize)>);
 
@@ -118,6 +118,8 @@ impl Track {
 
 pub struct Day09(Instructions);
 
+register!(9, Day09, "Rope Bridge");
+
 impl Solver for Day09 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
         let instructions: Instructions = parse_from_read(input)?;
@@ -125,16 +127,16 @@ impl Solver for Day09 {
         Ok(Day09(instructions))
     }
 
-    fn part_one(&self) -> String {
+    fn part_one(&self) -> anyhow::Result<Answer> {
         let track = Track::follow(Rope::default(), &self.0);
         let visits = track.tail_visited.len();
-        format!("{visits}")
+        Ok(visits.into())
     }
 
-    fn part_two(&self) -> String {
+    fn part_two(&self) -> anyhow::Result<Answer> {
         let track = Track::follow_long(LongRope::initial(10), &self.0);
         let visits = track.tail_visited.len();
-        format!("{visits}")
+        Ok(visits.into())
     }
 }
 
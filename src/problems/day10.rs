@@ -1,6 +1,6 @@
 use std::{collections::VecDeque, io::Read, str::FromStr};
 
-use super::{solutions::parse_lines, Solver};
+use super::{registry::register, solutions::parse_lines, Answer, Solver};
 
 use anyhow::{anyhow, bail};
 
@@ -87,7 +87,7 @@ impl Computer {
     pub fn draw(&mut self) -> String {
         let mut output = String::new();
         while let Some(d) = self.cycle() {
-            if self.cycles > 1 && (self.cycles - 1) % self.width == 0 {
+            if self.cycles > 1 && (self.cycles - 1).is_multiple_of(self.width) {
                 // XXX: Why > 1? It seemed to work, _shrug_
                 output.push('\n');
             }
@@ -103,6 +103,8 @@ impl Computer {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Day10(Computer);
 
+register!(10, Day10, "Cathode-Ray Tube");
+
 impl Day10 {
     pub fn strengths(&mut self, init: usize, step: usize) -> Vec<i64> {
         let computer = &mut self.0;
@@ -138,15 +140,15 @@ impl Solver for Day10 {
         Ok(Day10(Computer::new(instructions, 40)))
     }
 
-    fn part_one(&self) -> String {
+    fn part_one(&self) -> anyhow::Result<Answer> {
         let mut day = self.clone();
         let sum = day.strength_sum(20, 40);
-        format!("{sum}")
+        Ok(sum.into())
     }
 
-    fn part_two(&self) -> String {
+    fn part_two(&self) -> anyhow::Result<Answer> {
         let mut day = self.clone();
-        format!("\n{}", day.0.draw())
+        Ok(Answer::grid(&day.0.draw()))
     }
 }
 
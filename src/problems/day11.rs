@@ -4,7 +4,7 @@ use std::{
     str::FromStr,
 };
 
-use super::{solutions::parse_from_read, Solver};
+use super::{registry::register, solutions::parse_from_read, Answer, Solver};
 
 use pest::{iterators::Pair, Parser};
 use pest_derive::Parser;
@@ -15,6 +15,8 @@ pub struct MonkeyParser;
 
 pub struct Day11(Monkeys);
 
+register!(11, Day11, "Monkey in the Middle");
+
 impl Solver for Day11 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
         let monkeys: Monkeys = parse_from_read(input)?;
@@ -22,22 +24,22 @@ impl Solver for Day11 {
         Ok(Day11(monkeys))
     }
 
-    fn part_one(&self) -> String {
+    fn part_one(&self) -> anyhow::Result<Answer> {
         let mut monkeys = self.0.clone();
         monkeys.rounds(3).take(20).count();
 
         let (mx1, mx2) = monkeys.two_maxes();
         let mul = mx1 * mx2;
-        format!("{mul}")
+        Ok(mul.into())
     }
 
-    fn part_two(&self) -> String {
+    fn part_two(&self) -> anyhow::Result<Answer> {
         let mut monkeys = self.0.clone();
         monkeys.rounds(1).take(10000).count();
 
         let (mx1, mx2) = monkeys.two_maxes();
         let mul = mx1 * mx2;
-        format!("{mul}")
+        Ok(mul.into())
     }
 }
 
@@ -256,7 +258,7 @@ impl Monkeys {
     }
 
     #[allow(dead_code)]
-    pub fn tosses(&mut self, relief: i64) -> TossIter {
+    pub fn tosses(&mut self, relief: i64) -> TossIter<'_> {
         let remaining = self.monkeys.keys().copied().collect();
         TossIter {
             monkeys: self,
@@ -269,7 +271,7 @@ impl Monkeys {
         self.tosses(relief).count() as isize
     }
 
-    pub fn rounds(&mut self, relief: i64) -> RoundIter {
+    pub fn rounds(&mut self, relief: i64) -> RoundIter<'_> {
         RoundIter {
             monkeys: self,
             relief,
@@ -437,12 +439,12 @@ Monkey 0:
     #[test]
     fn test_part_one() {
         let day = Day11::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.part_one(), "10605");
+        assert_eq!(day.part_one().unwrap(), Answer::Int(10605));
     }
 
     #[test]
     fn test_part_two() {
         let day = Day11::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.part_two(), "2713310158");
+        assert_eq!(day.part_two().unwrap(), Answer::Int(2713310158));
     }
 }
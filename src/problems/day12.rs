@@ -8,10 +8,12 @@ use std::{
 use adventofcode2022::Position;
 use anyhow::{anyhow, bail};
 
-use super::{solutions::parse_from_read, Solver};
+use super::{registry::register, solutions::parse_from_read, Answer, Solver};
 
 pub struct Day12(Grid);
 
+register!(12, Day12, "Hill Climbing Algorithm");
+
 impl Day12 {}
 
 impl Solver for Day12 {
@@ -20,16 +22,22 @@ impl Solver for Day12 {
         Ok(Day12(grid))
     }
 
-    fn part_one(&self) -> String {
-        let path = self.0.shortest_path_from_start();
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let path = self
+            .0
+            .shortest_path_from_start()
+            .ok_or_else(|| anyhow!("The goal can't be reached from the start"))?;
         let steps = path.len() - 1;
-        format!("{steps}")
+        Ok(steps.into())
     }
 
-    fn part_two(&self) -> String {
-        let path = self.0.shortest_path_from_lowest();
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let path = self
+            .0
+            .shortest_path_from_lowest()
+            .ok_or_else(|| anyhow!("The goal can't be reached from any low point"))?;
         let steps = path.len() - 1;
-        format!("{steps}")
+        Ok(steps.into())
     }
 }
 
@@ -135,11 +143,11 @@ impl Grid {
         neighbors
     }
 
-    pub fn shortest_path_from_start(&self) -> Vec<Position> {
+    pub fn shortest_path_from_start(&self) -> Option<Vec<Position>> {
         self.shortest_path(vec![self.start])
     }
 
-    pub fn shortest_path_from_lowest(&self) -> Vec<Position> {
+    pub fn shortest_path_from_lowest(&self) -> Option<Vec<Position>> {
         let mut starts = Vec::new();
         let lowest = self[self.start];
         for (r, row) in self.rows.iter().enumerate() {
@@ -154,7 +162,9 @@ impl Grid {
         self.shortest_path(starts)
     }
 
-    pub fn shortest_path(&self, starts: Vec<Position>) -> Vec<Position> {
+    /// The shortest path from any of `starts` to the goal, or `None` if the
+    /// goal can't be reached
+    pub fn shortest_path(&self, starts: Vec<Position>) -> Option<Vec<Position>> {
         // Position -> (Shortest distance, prev)
         let mut visited: HashMap<Position, (i64, Option<Position>)> = HashMap::new();
         let mut queue = BinaryHeap::new();
@@ -213,11 +223,11 @@ impl Grid {
         let mut pos = Some(self.goal);
         while let Some(p) = pos {
             path.push(p);
-            pos = visited[&p].1;
+            pos = visited.get(&p)?.1;
         }
         path.reverse();
 
-        path
+        Some(path)
     }
 }
 
@@ -246,7 +256,7 @@ impl Ord for Visited {
 
 impl PartialOrd for Visited {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.as_key().cmp(&other.as_key()))
+        Some(self.cmp(other))
     }
 }
 
@@ -315,7 +325,7 @@ mod tests {
     #[test]
     fn test_shortest_path() {
         let grid = example();
-        let path = grid.shortest_path_from_start();
+        let path = grid.shortest_path_from_start().unwrap();
         // should be done in 31 steps = 32 positions
         assert_eq!(path.len(), 32);
         assert_eq!(path[0], grid.start);
@@ -325,7 +335,7 @@ mod tests {
     #[test]
     fn shortest_path_from_lowest() {
         let grid = example();
-        let path = grid.shortest_path_from_lowest();
+        let path = grid.shortest_path_from_lowest().unwrap();
         // should be done in 31 steps = 32 positions
         assert_eq!(path.len(), 30);
         assert_eq!(grid[path[0]], 0);
@@ -335,12 +345,19 @@ mod tests {
     #[test]
     fn test_part_one() {
         let day = Day12::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.part_one(), "31");
+        assert_eq!(day.part_one().unwrap(), Answer::Int(31));
     }
 
     #[test]
     fn test_part_two() {
         let day = Day12::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.part_two(), "29");
+        assert_eq!(day.part_two().unwrap(), Answer::Int(29));
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let day = Day12::from_input("Sab\nxyE".as_bytes()).unwrap();
+        assert!(day.part_one().is_err());
+        assert!(day.part_two().is_err());
     }
 }
@@ -1,34 +1,175 @@
-use std::io::Read;
+use std::{cmp::Ordering, fmt, io::Read, str::FromStr};
 
-use super::{solutions::parse_lines, Solver};
+use anyhow::{anyhow, bail};
 
-pub struct Day13(Vec<i64>);
+use super::{registry::register, solutions::parse_from_read, Answer, Solver};
+
+/// A packet is either a bare integer, or a list of packets
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Packet {
+    Int(i64),
+    List(Vec<Packet>),
+}
+
+impl Packet {
+    /// A divider packet, of the form `[[n]]`
+    pub fn divider(n: i64) -> Self {
+        Packet::List(vec![Packet::List(vec![Packet::Int(n)])])
+    }
+
+    // Parse a single packet from the front of `s`, returning the packet and
+    // the remainder of the string.
+    fn parse_prefix(s: &str) -> anyhow::Result<(Packet, &str)> {
+        if let Some(mut rest) = s.strip_prefix('[') {
+            let mut items = Vec::new();
+            if let Some(rest) = rest.strip_prefix(']') {
+                return Ok((Packet::List(items), rest));
+            }
+
+            loop {
+                let (item, r) = Packet::parse_prefix(rest)?;
+                items.push(item);
+                if let Some(r) = r.strip_prefix(',') {
+                    rest = r;
+                } else if let Some(r) = r.strip_prefix(']') {
+                    return Ok((Packet::List(items), r));
+                } else {
+                    bail!("Expected ',' or ']', found '{r}'");
+                }
+            }
+        }
+
+        let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        if end == 0 {
+            bail!("Expected '[' or a digit, found '{s}'");
+        }
+        let (digits, rest) = s.split_at(end);
+        Ok((Packet::Int(digits.parse()?), rest))
+    }
+}
+
+impl FromStr for Packet {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (packet, rest) = Packet::parse_prefix(s.trim())?;
+        if !rest.is_empty() {
+            bail!("Unexpected trailing input '{rest}' in packet '{s}'");
+        }
+        Ok(packet)
+    }
+}
+
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Packet::Int(n) => write!(f, "{n}"),
+            Packet::List(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Int(l), Packet::Int(r)) => l.cmp(r),
+            (Packet::List(l), Packet::List(r)) => l.cmp(r),
+            // Mixed types: the integer is promoted to a single-item list
+            (&Packet::Int(l), Packet::List(r)) => [Packet::Int(l)].as_slice().cmp(r.as_slice()),
+            (Packet::List(l), &Packet::Int(r)) => l.as_slice().cmp([Packet::Int(r)].as_slice()),
+        }
+    }
+}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pairs(Vec<(Packet, Packet)>);
+
+impl FromStr for Pairs {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pairs = Vec::new();
+        let mut lines = s.lines().map(str::trim).filter(|l| !l.is_empty());
+        while let Some(left) = lines.next() {
+            let right = lines
+                .next()
+                .ok_or_else(|| anyhow!("Packet '{left}' has no pair"))?;
+            pairs.push((left.parse()?, right.parse()?));
+        }
+
+        Ok(Pairs(pairs))
+    }
+}
+
+pub struct Day13(Pairs);
+
+register!(13, Day13, "Distress Signal");
 
 impl Day13 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+    /// Sum of the (1-based) indices of the pairs that are in the right order
+    pub fn ordered_index_sum(&self) -> usize {
+        let Day13(Pairs(pairs)) = self;
+        pairs
+            .iter()
+            .enumerate()
+            .filter(|(_, (l, r))| l < r)
+            .map(|(i, _)| i + 1)
+            .sum()
     }
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+    /// Product of the (1-based) positions of the divider packets once all
+    /// packets are sorted
+    pub fn decoder_key(&self) -> usize {
+        let Day13(Pairs(pairs)) = self;
+        let dividers = [Packet::divider(2), Packet::divider(6)];
+
+        // No need to actually sort: a divider's position is one more than the
+        // number of packets that come before it.
+        dividers
+            .iter()
+            .enumerate()
+            .map(|(i, d)| {
+                let before = pairs
+                    .iter()
+                    .flat_map(|(l, r)| [l, r])
+                    .filter(|&p| p < d)
+                    .count();
+                // Each earlier divider is also before this one
+                before + i + 1
+            })
+            .product()
     }
 }
 
 impl Solver for Day13 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day13(items))
+        let pairs = parse_from_read(input)?;
+        Ok(Day13(pairs))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let sum = self.ordered_index_sum();
+        Ok(sum.into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let key = self.decoder_key();
+        Ok(key.into())
     }
 }
 
@@ -41,20 +182,76 @@ mod tests {
     use super::*;
 
     const EXAMPLE: &str = r"
-        1
-        9
-        4
+        [1,1,3,1,1]
+        [1,1,5,1,1]
+
+        [[1],[2,3,4]]
+        [[1],4]
+
+        [9]
+        [[8,7,6]]
+
+        [[4,4],4,4]
+        [[4,4],4,4,4]
+
+        [7,7,7,7]
+        [7,7,7]
+
+        []
+        [3]
+
+        [[[]]]
+        [[]]
+
+        [1,[2,[3,[4,[5,6,7]]]],8,9]
+        [1,[2,[3,[4,[5,6,0]]]],8,9]
     ";
 
+    #[test]
+    fn test_parse() {
+        let pairs: Pairs = unindented(EXAMPLE).unwrap().parse().unwrap();
+        assert_eq!(pairs.0.len(), 8);
+        assert_eq!(
+            pairs.0[1].1,
+            Packet::List(vec![Packet::List(vec![Packet::Int(1)]), Packet::Int(4)])
+        );
+        assert_eq!(pairs.0[5].0, Packet::List(vec![]));
+
+        assert!("[1,2".parse::<Packet>().is_err());
+        assert!("[1,,2]".parse::<Packet>().is_err());
+        assert!("[1]]".parse::<Packet>().is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for line in unindented(EXAMPLE).unwrap().lines() {
+            if line.is_empty() {
+                continue;
+            }
+            let packet: Packet = line.parse().unwrap();
+            assert_eq!(packet.to_string(), line);
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        let pairs: Pairs = unindented(EXAMPLE).unwrap().parse().unwrap();
+        let ordered: Vec<bool> = pairs.0.iter().map(|(l, r)| l < r).collect();
+        assert_eq!(
+            ordered,
+            vec![true, true, false, true, false, true, false, false]
+        );
+    }
+
     #[test]
     fn test_part_one() {
         let day = Day13::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        assert_eq!(day.part_one().unwrap(), Answer::Int(13));
     }
 
     #[test]
     fn test_part_two() {
         let day = Day13::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(day.part_two().unwrap(), Answer::Int(140));
     }
 }
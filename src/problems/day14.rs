@@ -1,34 +1,186 @@
-use std::io::Read;
+use std::{collections::HashMap, fmt, io::Read, str::FromStr};
 
-use super::{solutions::parse_lines, Solver};
+use adventofcode2022::Position;
+use anyhow::{anyhow, bail};
 
-pub struct Day14(Vec<i64>);
+use super::{registry::register, solutions::parse_lines, Answer, Solver};
 
-impl Day14 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+/// Where the sand pours in from
+pub const SOURCE: Position = Position(500, 0);
+
+/// A single rock structure: a sequence of points connected by straight lines
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RockPath(Vec<Position>);
+
+impl FromStr for RockPath {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let points = s
+            .split(" -> ")
+            .map(|p| {
+                let (x, y) = p
+                    .trim()
+                    .split_once(',')
+                    .ok_or_else(|| anyhow!("Expected a point 'x,y', got '{p}'"))?;
+                Ok(Position(x.parse()?, y.parse()?))
+            })
+            .collect::<anyhow::Result<Vec<Position>>>()?;
+
+        if points.is_empty() {
+            bail!("Empty rock path");
+        }
+
+        Ok(RockPath(points))
+    }
+}
+
+impl RockPath {
+    /// All points covered by the path, including both ends of each segment
+    pub fn points(&self) -> impl Iterator<Item = Position> + '_ {
+        let first = self.0.first().copied().into_iter();
+        let rest = self.0.windows(2).flat_map(|w| {
+            let (from, to) = (w[0], w[1]);
+            let (dx, dy) = to - from;
+            let steps = dx.abs().max(dy.abs());
+            let step = (dx.signum(), dy.signum());
+            (1..=steps).map(move |n| from + (step.0 * n, step.1 * n))
+        });
+        first.chain(rest)
     }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Tile {
+    Rock,
+    Sand,
+}
+
+/// What happens below the lowest rock
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Floor {
+    /// Sand falls forever into the abyss
+    Abyss,
+    /// An infinite floor two below the lowest rock
+    Solid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cave {
+    tiles: HashMap<Position, Tile>,
+    // Lowest y-value of any rock (y increases downwards)
+    bottom: i64,
+}
+
+impl Cave {
+    pub fn new<'a>(paths: impl IntoIterator<Item = &'a RockPath>) -> Self {
+        let tiles: HashMap<Position, Tile> = paths
+            .into_iter()
+            .flat_map(|p| p.points())
+            .map(|p| (p, Tile::Rock))
+            .collect();
+        let bottom = tiles.keys().map(|p| p.1).max().unwrap_or(0);
+
+        Cave { tiles, bottom }
+    }
+
+    fn is_blocked(&self, pos: Position, floor: Floor) -> bool {
+        if floor == Floor::Solid && pos.1 >= self.bottom + 2 {
+            return true;
+        }
+        self.tiles.contains_key(&pos)
+    }
+
+    /// Pour sand in until it either falls into the abyss or blocks the source.
+    /// Returns the number of grains that came to rest.
+    ///
+    /// Rather than dropping every grain from the source, this keeps the path
+    /// of the previous grain: the next grain follows the same path up until
+    /// the point where the last one came to rest, so we can resume from just
+    /// before there.
+    pub fn fill(&mut self, floor: Floor) -> usize {
+        let mut rested = 0;
+        let mut path = vec![SOURCE];
+        if self.is_blocked(SOURCE, floor) {
+            return 0;
+        }
+
+        while let Some(&pos) = path.last() {
+            let next = [(0, 1), (-1, 1), (1, 1)]
+                .into_iter()
+                .map(|d| pos + d)
+                .find(|&p| !self.is_blocked(p, floor));
+
+            match next {
+                Some(p) if floor == Floor::Abyss && p.1 > self.bottom => {
+                    // Everything from here on falls forever
+                    break;
+                }
+                Some(p) => path.push(p),
+                None => {
+                    self.tiles.insert(pos, Tile::Sand);
+                    rested += 1;
+                    path.pop();
+                }
+            }
+        }
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+        rested
     }
 }
 
+impl fmt::Display for Cave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let points = self.tiles.keys().chain(std::iter::once(&SOURCE));
+        let (mut min_x, mut max_x, mut min_y, mut max_y) = (SOURCE.0, SOURCE.0, SOURCE.1, SOURCE.1);
+        for &Position(x, y) in points {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
+        }
+
+        for y in min_y..=max_y {
+            if y > min_y {
+                writeln!(f)?;
+            }
+            for x in min_x..=max_x {
+                let p = Position(x, y);
+                let c = match self.tiles.get(&p) {
+                    Some(Tile::Rock) => '#',
+                    Some(Tile::Sand) => 'o',
+                    None if p == SOURCE => '+',
+                    None => '.',
+                };
+                write!(f, "{c}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Day14(Cave);
+
+register!(14, Day14, "Regolith Reservoir");
+
 impl Solver for Day14 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day14(items))
+        let paths = parse_lines::<RockPath>(input)?;
+        Ok(Day14(Cave::new(&paths)))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let mut cave = self.0.clone();
+        let count = cave.fill(Floor::Abyss);
+        log::debug!("Final cave:\n{cave}");
+        Ok(count.into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let mut cave = self.0.clone();
+        let count = cave.fill(Floor::Solid);
+        Ok(count.into())
     }
 }
 
@@ -41,20 +193,77 @@ mod tests {
     use super::*;
 
     const EXAMPLE: &str = r"
-        1
-        9
-        4
+        498,4 -> 498,6 -> 496,6
+        503,4 -> 502,4 -> 502,9 -> 494,9
+    ";
+
+    fn example() -> Cave {
+        let paths = parse_lines::<RockPath>(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
+        Cave::new(&paths)
+    }
+
+    #[test]
+    fn test_parse() {
+        let path: RockPath = "498,4 -> 498,6 -> 496,6".parse().unwrap();
+        let points: Vec<Position> = path.points().collect();
+        assert_eq!(
+            points,
+            vec![
+                Position(498, 4),
+                Position(498, 5),
+                Position(498, 6),
+                Position(497, 6),
+                Position(496, 6),
+            ]
+        );
+
+        assert!("498,4 -> 498".parse::<RockPath>().is_err());
+    }
+
+    const EXPECTED_EMPTY: &str = r"
+        ......+...
+        ..........
+        ..........
+        ..........
+        ....#...##
+        ....#...#.
+        ..###...#.
+        ........#.
+        ........#.
+        #########.
     ";
 
+    const EXPECTED_FULL: &str = r"
+        ......+...
+        ..........
+        ......o...
+        .....ooo..
+        ....#ooo##
+        ...o#ooo#.
+        ..###ooo#.
+        ....oooo#.
+        .o.ooooo#.
+        #########.
+    ";
+
+    #[test]
+    fn test_display() {
+        let mut cave = example();
+        assert_eq!(cave.to_string(), unindented(EXPECTED_EMPTY).unwrap());
+
+        assert_eq!(cave.fill(Floor::Abyss), 24);
+        assert_eq!(cave.to_string(), unindented(EXPECTED_FULL).unwrap());
+    }
+
     #[test]
     fn test_part_one() {
         let day = Day14::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        assert_eq!(day.part_one().unwrap(), Answer::Int(24));
     }
 
     #[test]
     fn test_part_two() {
         let day = Day14::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(day.part_two().unwrap(), Answer::Int(93));
     }
 }
@@ -1,34 +1,247 @@
-use std::io::Read;
+use std::{collections::HashSet, io::Read, ops::RangeInclusive, str::FromStr};
 
-use super::{solutions::parse_lines, Solver};
+use adventofcode2022::Position;
+use anyhow::anyhow;
 
-pub struct Day15(Vec<i64>);
+use super::{registry::register, solutions::parse_lines, Answer, Solver};
+
+/// The row checked in part one, for the real input
+pub const ROW: i64 = 2_000_000;
+/// The distress beacon is somewhere in `0..=BOUND` for both x and y
+pub const BOUND: i64 = 4_000_000;
+/// The same, for the example, which is on a much smaller scale
+pub const EXAMPLE_ROW: i64 = 10;
+pub const EXAMPLE_BOUND: i64 = 20;
+/// Inputs with every coordinate smaller than this are taken to be examples
+const EXAMPLE_SCALE: i64 = 1_000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Sensor {
+    pub position: Position,
+    pub beacon: Position,
+}
+
+impl FromStr for Sensor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_pos = |p: &str| -> anyhow::Result<Position> {
+            let (x, y) = p
+                .trim()
+                .split_once(", ")
+                .ok_or_else(|| anyhow!("Expected 'x=.., y=..', got '{p}'"))?;
+            let x = x
+                .strip_prefix("x=")
+                .ok_or_else(|| anyhow!("Expected 'x=', got '{x}'"))?;
+            let y = y
+                .strip_prefix("y=")
+                .ok_or_else(|| anyhow!("Expected 'y=', got '{y}'"))?;
+            Ok(Position(x.parse()?, y.parse()?))
+        };
+
+        let rest = s
+            .strip_prefix("Sensor at ")
+            .ok_or_else(|| anyhow!("Expected 'Sensor at', got '{s}'"))?;
+        let (sensor, beacon) = rest
+            .split_once(": closest beacon is at ")
+            .ok_or_else(|| anyhow!("Expected ': closest beacon is at' in '{s}'"))?;
+
+        Ok(Sensor {
+            position: parse_pos(sensor)?,
+            beacon: parse_pos(beacon)?,
+        })
+    }
+}
+
+impl Sensor {
+    pub fn radius(&self) -> i64 {
+        Position::from(self.beacon - self.position).manhattan()
+    }
+
+    pub fn covers(&self, pos: Position) -> bool {
+        Position::from(pos - self.position).manhattan() <= self.radius()
+    }
+
+    /// The x-values this sensor rules out on row `y`, if any
+    pub fn row_interval(&self, y: i64) -> Option<RangeInclusive<i64>> {
+        let Position(sx, sy) = self.position;
+        let half_width = self.radius() - (y - sy).abs();
+        if half_width < 0 {
+            return None;
+        }
+        Some(sx - half_width..=sx + half_width)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Day15 {
+    sensors: Vec<Sensor>,
+    row: i64,
+    bound: i64,
+}
+
+register!(15, Day15, "Beacon Exclusion Zone");
 
 impl Day15 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+    /// The puzzle uses different constants for the example than for the real
+    /// input, without saying which is which, so by default go by the size of
+    /// the input. Use [`Day15::with_params`] to pick them instead.
+    pub fn new(sensors: Vec<Sensor>) -> Self {
+        let small = sensors.iter().all(|s| {
+            [s.position, s.beacon]
+                .iter()
+                .all(|p| p.0.abs() < EXAMPLE_SCALE && p.1.abs() < EXAMPLE_SCALE)
+        });
+        let (row, bound) = if small {
+            (EXAMPLE_ROW, EXAMPLE_BOUND)
+        } else {
+            (ROW, BOUND)
+        };
+        Day15 {
+            sensors,
+            row,
+            bound,
+        }
+    }
+
+    /// Override the row checked in part one and the bound of the search area
+    #[allow(dead_code)]
+    pub fn with_params(self, row: i64, bound: i64) -> Self {
+        Day15 { row, bound, ..self }
+    }
+
+    /// The merged, sorted, non-overlapping intervals covered on row `y`
+    pub fn row_coverage(&self, y: i64) -> Vec<RangeInclusive<i64>> {
+        let mut intervals: Vec<RangeInclusive<i64>> = self
+            .sensors
+            .iter()
+            .filter_map(|s| s.row_interval(y))
+            .collect();
+        intervals.sort_by_key(|r| (*r.start(), *r.end()));
+
+        let mut merged: Vec<RangeInclusive<i64>> = Vec::with_capacity(intervals.len());
+        for r in intervals {
+            match merged.last_mut() {
+                // Adjacent intervals get merged too, as there's no gap
+                Some(last) if *r.start() <= *last.end() + 1 => {
+                    if r.end() > last.end() {
+                        *last = *last.start()..=*r.end();
+                    }
+                }
+                _ => merged.push(r),
+            }
+        }
+
+        merged
+    }
+
+    /// Number of positions on row `y` that cannot contain a beacon
+    pub fn excluded_count(&self, y: i64) -> i64 {
+        let coverage = self.row_coverage(y);
+        let covered: i64 = coverage.iter().map(|r| r.end() - r.start() + 1).sum();
+
+        let beacons: HashSet<Position> = self
+            .sensors
+            .iter()
+            .map(|s| s.beacon)
+            .filter(|b| b.1 == y && coverage.iter().any(|r| r.contains(&b.0)))
+            .collect();
+
+        covered - beacons.len() as i64
+    }
+
+    /// Find the one position in the `0..=bound` square not covered by any
+    /// sensor.
+    ///
+    /// If there's exactly one such position, it must sit just outside the
+    /// edge of several sensors' diamonds, so it lies on the intersection of
+    /// two of those diagonal edges. On the square's own edges, one diagonal
+    /// and the edge can be enough to hem it in, so the points where the
+    /// diagonals cross the edges count too, along with the corners. That gives
+    /// a small candidate set to check, rather than sweeping every row.
+    pub fn distress_beacon(&self) -> Option<Position> {
+        let in_bounds =
+            |p: Position| (0..=self.bound).contains(&p.0) && (0..=self.bound).contains(&p.1);
+
+        // Lines are y = x + a (rising) and y = -x + b (falling)
+        let mut rising = HashSet::new();
+        let mut falling = HashSet::new();
+        for s in &self.sensors {
+            let Position(x, y) = s.position;
+            let r = s.radius() + 1;
+            rising.insert(y - x + r);
+            rising.insert(y - x - r);
+            falling.insert(y + x + r);
+            falling.insert(y + x - r);
+        }
+
+        let intersections = rising.iter().flat_map(|&a| {
+            falling.iter().filter_map(move |&b| {
+                // Only integer intersections are real positions
+                if (b - a) % 2 != 0 {
+                    return None;
+                }
+                let x = (b - a) / 2;
+                Some(Position(x, x + a))
+            })
+        });
+
+        let bound = self.bound;
+        let edges = rising
+            .iter()
+            .flat_map(|&a| {
+                [
+                    Position(0, a),
+                    Position(bound, bound + a),
+                    Position(-a, 0),
+                    Position(bound - a, bound),
+                ]
+            })
+            .chain(falling.iter().flat_map(|&b| {
+                [
+                    Position(0, b),
+                    Position(bound, b - bound),
+                    Position(b, 0),
+                    Position(b - bound, bound),
+                ]
+            }));
+
+        let corners = [
+            Position(0, 0),
+            Position(bound, 0),
+            Position(0, bound),
+            Position(bound, bound),
+        ];
+
+        intersections
+            .chain(edges)
+            .chain(corners)
+            .filter(|&p| in_bounds(p))
+            .find(|&p| !self.sensors.iter().any(|s| s.covers(p)))
     }
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+    pub fn tuning_frequency(&self) -> Option<i64> {
+        let Position(x, y) = self.distress_beacon()?;
+        Some(x * 4_000_000 + y)
     }
 }
 
 impl Solver for Day15 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day15(items))
+        let sensors = parse_lines::<Sensor>(input)?;
+        Ok(Day15::new(sensors))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let count = self.excluded_count(self.row);
+        Ok(count.into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let freq = self
+            .tuning_frequency()
+            .ok_or_else(|| anyhow!("No uncovered position found"))?;
+        Ok(freq.into())
     }
 }
 
@@ -41,20 +254,88 @@ mod tests {
     use super::*;
 
     const EXAMPLE: &str = r"
-        1
-        9
-        4
+        Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+        Sensor at x=9, y=16: closest beacon is at x=10, y=16
+        Sensor at x=13, y=2: closest beacon is at x=15, y=3
+        Sensor at x=12, y=14: closest beacon is at x=10, y=16
+        Sensor at x=10, y=20: closest beacon is at x=10, y=16
+        Sensor at x=14, y=17: closest beacon is at x=10, y=16
+        Sensor at x=8, y=7: closest beacon is at x=2, y=10
+        Sensor at x=2, y=0: closest beacon is at x=2, y=10
+        Sensor at x=0, y=11: closest beacon is at x=2, y=10
+        Sensor at x=20, y=14: closest beacon is at x=25, y=17
+        Sensor at x=17, y=20: closest beacon is at x=21, y=22
+        Sensor at x=16, y=7: closest beacon is at x=15, y=3
+        Sensor at x=14, y=3: closest beacon is at x=15, y=3
+        Sensor at x=20, y=1: closest beacon is at x=15, y=3
     ";
 
+    fn example() -> Day15 {
+        Day15::from_input(unindented(EXAMPLE).unwrap().as_bytes())
+            .unwrap()
+            .with_params(EXAMPLE_ROW, EXAMPLE_BOUND)
+    }
+
     #[test]
-    fn test_part_one() {
+    fn test_parse() {
+        let sensor: Sensor = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15"
+            .parse()
+            .unwrap();
+        assert_eq!(sensor.position, Position(2, 18));
+        assert_eq!(sensor.beacon, Position(-2, 15));
+        assert_eq!(sensor.radius(), 7);
+
+        // The example's constants are picked by default, from its size
         let day = Day15::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        assert_eq!(day.sensors.len(), 14);
+        assert_eq!((day.row, day.bound), (EXAMPLE_ROW, EXAMPLE_BOUND));
+
+        let day = day.with_params(11, 30);
+        assert_eq!((day.row, day.bound), (11, 30));
+    }
+
+    #[test]
+    fn test_row_coverage() {
+        let day = example();
+        assert_eq!(day.row_coverage(10), vec![-2..=24]);
+        assert_eq!(day.row_coverage(11), vec![-3..=13, 15..=25]);
+    }
+
+    #[test]
+    fn test_distress_beacon() {
+        let day = example();
+        assert_eq!(day.distress_beacon(), Some(Position(14, 11)));
+    }
+
+    #[test]
+    fn test_beacon_on_edge() {
+        // Only (0, 7) is uncovered, and the diagonals next to it all run the
+        // same way, so it's not where any two of them cross
+        let sensors = [
+            ((-3, 12), 7),
+            ((4, 2), 8),
+            ((12, 3), 14),
+            ((20, 10), 21),
+            ((-1, 11), 3),
+            ((9, 18), 18),
+        ]
+        .into_iter()
+        .map(|((x, y), r)| Sensor {
+            position: Position(x, y),
+            beacon: Position(x + r, y),
+        })
+        .collect();
+        let day = Day15::new(sensors).with_params(0, 20);
+        assert_eq!(day.distress_beacon(), Some(Position(0, 7)));
+    }
+
+    #[test]
+    fn test_part_one() {
+        assert_eq!(example().part_one().unwrap(), Answer::Int(26));
     }
 
     #[test]
     fn test_part_two() {
-        let day = Day15::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(example().part_two().unwrap(), Answer::Int(56000011));
     }
 }
@@ -1,34 +1,280 @@
-use std::io::Read;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    io::Read,
+    str::FromStr,
+};
 
-use super::{solutions::parse_lines, Solver};
+use anyhow::{anyhow, bail};
 
-pub struct Day16(Vec<i64>);
+use super::{registry::register, solutions::parse_lines, Answer, Solver};
 
-impl Day16 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+const START: &str = "AA";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Valve {
+    name: String,
+    flow: i64,
+    tunnels: Vec<String>,
+}
+
+impl FromStr for Valve {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+        let rest = s
+            .strip_prefix("Valve ")
+            .ok_or_else(|| anyhow!("Expected 'Valve', got '{s}'"))?;
+        let (name, rest) = rest
+            .split_once(" has flow rate=")
+            .ok_or_else(|| anyhow!("Expected flow rate in '{s}'"))?;
+        let (flow, rest) = rest
+            .split_once("; ")
+            .ok_or_else(|| anyhow!("Expected ';' in '{s}'"))?;
+
+        // The grammar changes for a single tunnel
+        let tunnels = ["tunnels lead to valves ", "tunnel leads to valve "]
+            .iter()
+            .find_map(|prefix| rest.strip_prefix(prefix))
+            .ok_or_else(|| anyhow!("Expected tunnel list in '{s}'"))?;
+
+        Ok(Valve {
+            name: name.to_owned(),
+            flow: flow.parse()?,
+            tunnels: tunnels.split(", ").map(str::to_owned).collect(),
+        })
+    }
+}
+
+/// The valve graph, compressed down to the valves with non-zero flow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    // Names and flows of the useful valves; indices here are bits in a mask
+    names: Vec<String>,
+    flows: Vec<i64>,
+    // dist[i][j] is the number of minutes to walk from valve i to valve j.
+    // Index names.len() is the starting valve.
+    dist: Vec<Vec<i64>>,
+}
+
+impl Network {
+    pub fn new(valves: &[Valve]) -> anyhow::Result<Self> {
+        let index: HashMap<&str, usize> = valves
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (v.name.as_str(), i))
+            .collect();
+
+        let mut neighbors = Vec::with_capacity(valves.len());
+        for v in valves {
+            let ns = v
+                .tunnels
+                .iter()
+                .map(|t| {
+                    index
+                        .get(t.as_str())
+                        .copied()
+                        .ok_or_else(|| anyhow!("Valve {} has tunnel to unknown {t}", v.name))
+                })
+                .collect::<anyhow::Result<Vec<usize>>>()?;
+            neighbors.push(ns);
+        }
+
+        let Some(&start) = index.get(START) else {
+            bail!("No starting valve {START}");
+        };
+
+        let useful: Vec<usize> = (0..valves.len()).filter(|&i| valves[i].flow > 0).collect();
+        if useful.len() > 63 {
+            bail!("Too many valves with flow: {}", useful.len());
+        }
+
+        // BFS from each useful valve, and the start
+        let mut dist = Vec::with_capacity(useful.len() + 1);
+        for &from in useful.iter().chain(std::iter::once(&start)) {
+            let mut seen = vec![None; valves.len()];
+            seen[from] = Some(0);
+            let mut queue = VecDeque::from([from]);
+            while let Some(v) = queue.pop_front() {
+                let d = seen[v].unwrap();
+                for &n in &neighbors[v] {
+                    if seen[n].is_none() {
+                        seen[n] = Some(d + 1);
+                        queue.push_back(n);
+                    }
+                }
+            }
+
+            let row = useful
+                .iter()
+                // Unreachable valves are as good as infinitely far away
+                .map(|&to| seen[to].unwrap_or(i64::MAX / 2))
+                .collect();
+            dist.push(row);
+        }
+
+        Ok(Network {
+            names: useful.iter().map(|&i| valves[i].name.clone()).collect(),
+            flows: useful.iter().map(|&i| valves[i].flow).collect(),
+            dist,
+        })
+    }
+
+    /// The best plan for every set of opened valves, within `minutes`.
+    ///
+    /// The key is a bitmask of the valves opened, and every reachable set is
+    /// included, not just the maximal ones.
+    pub fn best_plans(&self, minutes: i64) -> HashMap<u64, Plan> {
+        let mut best = HashMap::new();
+        let mut path = Vec::new();
+        self.search(
+            self.names.len(),
+            minutes,
+            minutes,
+            0,
+            0,
+            &mut path,
+            &mut best,
+        );
+        best
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &self,
+        pos: usize,
+        minutes: i64,
+        remaining: i64,
+        opened: u64,
+        pressure: i64,
+        path: &mut Vec<Opening>,
+        best: &mut HashMap<u64, Plan>,
+    ) {
+        let entry = best.entry(opened).or_insert_with(|| Plan {
+            pressure: -1,
+            openings: Vec::new(),
+        });
+        if pressure > entry.pressure {
+            *entry = Plan {
+                pressure,
+                openings: path.clone(),
+            };
+        }
+
+        for next in 0..self.names.len() {
+            if opened & (1 << next) != 0 {
+                continue;
+            }
+            // Walk there, then a minute to open it
+            let left = remaining - self.dist[pos][next] - 1;
+            if left <= 0 {
+                continue;
+            }
+
+            path.push(Opening {
+                valve: self.names[next].clone(),
+                minute: minutes - left,
+            });
+            self.search(
+                next,
+                minutes,
+                left,
+                opened | (1 << next),
+                pressure + left * self.flows[next],
+                path,
+                best,
+            );
+            path.pop();
+        }
     }
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+    /// The best plan for a single agent
+    pub fn solo(&self, minutes: i64) -> Plan {
+        self.best_plans(minutes)
+            .into_values()
+            .max_by_key(|p| p.pressure)
+            .unwrap_or_default()
     }
+
+    /// The best pair of plans for two agents working in parallel. They never
+    /// open the same valve, so we only need to combine disjoint sets.
+    pub fn pair(&self, minutes: i64) -> (Plan, Plan) {
+        let mut plans: Vec<(u64, Plan)> = self.best_plans(minutes).into_iter().collect();
+        plans.sort_by_key(|(_, p)| -p.pressure);
+
+        let mut best: Option<(i64, usize, usize)> = None;
+        for (i, (m1, p1)) in plans.iter().enumerate() {
+            if let Some((b, _, _)) = best {
+                // Plans are sorted, so the partner can't be any better than p1
+                if p1.pressure * 2 <= b {
+                    break;
+                }
+            }
+            for (j, (m2, p2)) in plans.iter().enumerate().skip(i) {
+                if m1 & m2 != 0 {
+                    continue;
+                }
+                let total = p1.pressure + p2.pressure;
+                if best.is_none_or(|(b, _, _)| total > b) {
+                    best = Some((total, i, j));
+                }
+                // Everything after this is no better
+                break;
+            }
+        }
+
+        match best {
+            Some((_, i, j)) => (plans[i].1.clone(), plans[j].1.clone()),
+            None => Default::default(),
+        }
+    }
+}
+
+/// A valve, and the minute at which it was opened
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Opening {
+    pub valve: String,
+    pub minute: i64,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Plan {
+    pub pressure: i64,
+    pub openings: Vec<Opening>,
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.pressure)?;
+        for Opening { valve, minute } in &self.openings {
+            write!(f, " {valve}@{minute}")?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Day16(Network);
+
+register!(16, Day16, "Proboscidea Volcanium");
+
 impl Solver for Day16 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day16(items))
+        let valves = parse_lines::<Valve>(input)?;
+        Ok(Day16(Network::new(&valves)?))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let plan = self.0.solo(30);
+        log::info!("Plan: {plan}");
+        Ok(plan.pressure.into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let (me, elephant) = self.0.pair(26);
+        log::info!("Me: {me}");
+        log::info!("Elephant: {elephant}");
+        Ok((me.pressure + elephant.pressure).into())
     }
 }
 
@@ -41,20 +287,62 @@ mod tests {
     use super::*;
 
     const EXAMPLE: &str = r"
-        1
-        9
-        4
+        Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+        Valve BB has flow rate=13; tunnels lead to valves CC, AA
+        Valve CC has flow rate=2; tunnels lead to valves DD, BB
+        Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+        Valve EE has flow rate=3; tunnels lead to valves FF, DD
+        Valve FF has flow rate=0; tunnels lead to valves EE, GG
+        Valve GG has flow rate=0; tunnels lead to valves FF, HH
+        Valve HH has flow rate=22; tunnel leads to valve GG
+        Valve II has flow rate=0; tunnels lead to valves AA, JJ
+        Valve JJ has flow rate=21; tunnel leads to valve II
     ";
 
+    fn example() -> Network {
+        let valves = parse_lines::<Valve>(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
+        Network::new(&valves).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let valve: Valve = "Valve HH has flow rate=22; tunnel leads to valve GG"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            valve,
+            Valve {
+                name: "HH".to_owned(),
+                flow: 22,
+                tunnels: vec!["GG".to_owned()],
+            }
+        );
+
+        let network = example();
+        assert_eq!(network.names, vec!["BB", "CC", "DD", "EE", "HH", "JJ"]);
+        // From AA
+        assert_eq!(network.dist[6], vec![1, 2, 1, 2, 5, 2]);
+    }
+
+    #[test]
+    fn test_solo_plan() {
+        let plan = example().solo(30);
+        assert_eq!(plan.pressure, 1651);
+        let order: Vec<&str> = plan.openings.iter().map(|o| o.valve.as_str()).collect();
+        assert_eq!(order, vec!["DD", "BB", "JJ", "HH", "EE", "CC"]);
+        assert_eq!(plan.openings[0].minute, 2);
+        assert_eq!(plan.openings[5].minute, 24);
+    }
+
     #[test]
     fn test_part_one() {
         let day = Day16::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        assert_eq!(day.part_one().unwrap(), Answer::Int(1651));
     }
 
     #[test]
     fn test_part_two() {
         let day = Day16::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(day.part_two().unwrap(), Answer::Int(1707));
     }
 }
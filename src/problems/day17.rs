@@ -1,34 +1,245 @@
-use std::io::Read;
+use std::{collections::HashMap, fmt, io::Read, str::FromStr};
 
-use super::{solutions::parse_lines, Solver};
+use anyhow::bail;
 
-pub struct Day17(Vec<i64>);
+use super::{registry::register, solutions::parse_from_read, Answer, Solver};
 
-impl Day17 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+const WIDTH: usize = 7;
+
+// Rocks, as rows from the bottom up. Bit x is set if column x is filled, with
+// the rock's left edge at column 0.
+const ROCKS: [&[u8]; 5] = [
+    // ####
+    &[0b1111],
+    // .#.
+    // ###
+    // .#.
+    &[0b010, 0b111, 0b010],
+    // ..#
+    // ..#
+    // ###
+    &[0b111, 0b100, 0b100],
+    // #
+    // #
+    // #
+    // #
+    &[0b1, 0b1, 0b1, 0b1],
+    // ##
+    // ##
+    &[0b11, 0b11],
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Jet {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Jets(Vec<Jet>);
+
+impl FromStr for Jets {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let jets = s
+            .trim()
+            .chars()
+            .map(|c| match c {
+                '<' => Ok(Jet::Left),
+                '>' => Ok(Jet::Right),
+                _ => bail!("Unexpected jet '{c}'"),
+            })
+            .collect::<anyhow::Result<Vec<Jet>>>()?;
+
+        if jets.is_empty() {
+            bail!("Empty jet pattern");
+        }
+
+        Ok(Jets(jets))
+    }
+}
+
+/// A snapshot of everything that determines how the rest of the simulation
+/// goes: which rock and jet come next, and the shape of the top of the tower
+/// (as the depth of each column below the top).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Fingerprint {
+    rock: usize,
+    jet: usize,
+    profile: [usize; WIDTH],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chamber<'a> {
+    jets: &'a [Jet],
+    // One bitmask per row, from the floor up
+    rows: Vec<u8>,
+    next_rock: usize,
+    next_jet: usize,
+    dropped: usize,
+}
+
+impl<'a> Chamber<'a> {
+    pub fn new(jets: &'a Jets) -> Self {
+        Chamber {
+            jets: &jets.0,
+            rows: Vec::new(),
+            next_rock: 0,
+            next_jet: 0,
+            dropped: 0,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    // Does the rock overlap the tower, with its bottom row at y? Walls are
+    // handled when pushing.
+    fn collides(&self, rock: &[u8], y: usize) -> bool {
+        rock.iter()
+            .enumerate()
+            .any(|(dy, &r)| self.rows.get(y + dy).is_some_and(|&row| row & r != 0))
+    }
+
+    fn push(rock: &[u8], jet: Jet) -> Option<Vec<u8>> {
+        let edge = match jet {
+            Jet::Left => 1,
+            Jet::Right => 1 << (WIDTH - 1),
+        };
+        if rock.iter().any(|&r| r & edge != 0) {
+            return None;
+        }
+
+        Some(
+            rock.iter()
+                .map(|&r| match jet {
+                    Jet::Left => r >> 1,
+                    Jet::Right => r << 1,
+                })
+                .collect(),
+        )
+    }
+
+    /// Drop a single rock, until it comes to rest
+    pub fn drop_rock(&mut self) {
+        let mut rock: Vec<u8> = ROCKS[self.next_rock].iter().map(|&r| r << 2).collect();
+        self.next_rock = (self.next_rock + 1) % ROCKS.len();
+        let mut y = self.height() + 3;
+
+        loop {
+            let jet = self.jets[self.next_jet];
+            self.next_jet = (self.next_jet + 1) % self.jets.len();
+
+            if let Some(pushed) = Self::push(&rock, jet) {
+                if !self.collides(&pushed, y) {
+                    rock = pushed;
+                }
+            }
+
+            if y == 0 || self.collides(&rock, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+
+        for (dy, r) in rock.into_iter().enumerate() {
+            match self.rows.get_mut(y + dy) {
+                Some(row) => *row |= r,
+                None => self.rows.push(r),
+            }
+        }
+        self.dropped += 1;
+    }
+
+    fn fingerprint(&self) -> Fingerprint {
+        let height = self.height();
+        let mut profile = [height; WIDTH];
+        for (x, depth) in profile.iter_mut().enumerate() {
+            if let Some(top) = self.rows.iter().rposition(|&r| r & (1 << x) != 0) {
+                *depth = height - 1 - top;
+            }
+        }
+
+        Fingerprint {
+            rock: self.next_rock,
+            jet: self.next_jet,
+            profile,
+        }
+    }
+
+    /// The height of the tower once `count` rocks have been dropped in total.
+    ///
+    /// Once the same fingerprint shows up twice, the rocks in between form a
+    /// cycle that will repeat forever, so we skip ahead by whole cycles and
+    /// only simulate the remainder.
+    pub fn tower_height(&mut self, count: usize) -> usize {
+        let mut seen: HashMap<Fingerprint, (usize, usize)> = HashMap::new();
+        let mut skipped = 0;
+
+        while self.dropped < count {
+            self.drop_rock();
+            if skipped > 0 {
+                continue;
+            }
+
+            let key = self.fingerprint();
+            if let Some(&(prev_dropped, prev_height)) = seen.get(&key) {
+                let period = self.dropped - prev_dropped;
+                let growth = self.height() - prev_height;
+                let cycles = (count - self.dropped) / period;
+                log::info!(
+                    "Cycle of {period} rocks (+{growth} height) found after {} rocks",
+                    self.dropped
+                );
+
+                self.dropped += cycles * period;
+                skipped = cycles * growth;
+                continue;
+            }
+            seen.insert(key, (self.dropped, self.height()));
+        }
+
+        self.height() + skipped
     }
+}
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+impl fmt::Display for Chamber<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &row in self.rows.iter().rev() {
+            f.write_str("|")?;
+            for x in 0..WIDTH {
+                let c = if row & (1 << x) != 0 { '#' } else { '.' };
+                write!(f, "{c}")?;
+            }
+            writeln!(f, "|")?;
+        }
+        write!(f, "+{}+", "-".repeat(WIDTH))
     }
 }
 
+pub struct Day17(Jets);
+
+register!(17, Day17, "Pyroclastic Flow");
+
 impl Solver for Day17 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day17(items))
+        let jets = parse_from_read(input)?;
+        Ok(Day17(jets))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let mut chamber = Chamber::new(&self.0);
+        let height = chamber.tower_height(2022);
+        log::trace!("Final chamber:\n{chamber}");
+        Ok(height.into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let mut chamber = Chamber::new(&self.0);
+        let height = chamber.tower_height(1_000_000_000_000);
+        Ok(height.into())
     }
 }
 
@@ -40,21 +251,49 @@ mod tests {
 
     use super::*;
 
-    const EXAMPLE: &str = r"
-        1
-        9
-        4
+    const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+    const EXPECTED_THREE: &str = r"
+        |..#....|
+        |..#....|
+        |####...|
+        |..###..|
+        |...#...|
+        |..####.|
+        +-------+
     ";
 
+    #[test]
+    fn test_drop() {
+        let jets: Jets = EXAMPLE.parse().unwrap();
+        let mut chamber = Chamber::new(&jets);
+        chamber.drop_rock();
+        assert_eq!(chamber.to_string(), "|..####.|\n+-------+");
+        chamber.drop_rock();
+        chamber.drop_rock();
+        assert_eq!(chamber.to_string(), unindented(EXPECTED_THREE).unwrap());
+    }
+
+    #[test]
+    fn test_no_cycle_skip() {
+        // Simulating every rock gives the same answer as skipping cycles
+        let jets: Jets = EXAMPLE.parse().unwrap();
+        let mut chamber = Chamber::new(&jets);
+        for _ in 0..2022 {
+            chamber.drop_rock();
+        }
+        assert_eq!(chamber.height(), 3068);
+    }
+
     #[test]
     fn test_part_one() {
-        let day = Day17::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        let day = Day17::from_input(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(day.part_one().unwrap(), Answer::Int(3068));
     }
 
     #[test]
     fn test_part_two() {
-        let day = Day17::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        let day = Day17::from_input(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(day.part_two().unwrap(), Answer::Int(1514285714288));
     }
 }
@@ -1,34 +1,27 @@
 use std::io::Read;
 
-use super::{solutions::parse_lines, Solver};
+use adventofcode2022::{Voxel, VoxelSet};
 
-pub struct Day18(Vec<i64>);
+use super::{registry::register, solutions::parse_lines, Answer, Solver};
 
-impl Day18 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
-    }
+pub struct Day18(VoxelSet);
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
-    }
-}
+register!(18, Day18, "Boiling Boulders");
 
 impl Solver for Day18 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day18(items))
+        let cubes = parse_lines::<Voxel>(input)?;
+        Ok(Day18(cubes.into_iter().collect()))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let area = self.0.surface_area();
+        Ok(area.into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let area = self.0.exterior_surface_area();
+        Ok(area.into())
     }
 }
 
@@ -41,20 +34,37 @@ mod tests {
     use super::*;
 
     const EXAMPLE: &str = r"
-        1
-        9
-        4
+        2,2,2
+        1,2,2
+        3,2,2
+        2,1,2
+        2,3,2
+        2,2,1
+        2,2,3
+        2,2,4
+        2,2,6
+        1,2,5
+        3,2,5
+        2,1,5
+        2,3,5
     ";
 
+    #[test]
+    fn test_small() {
+        let day = Day18::from_input("1,1,1\n2,1,1".as_bytes()).unwrap();
+        assert_eq!(day.0.len(), 2);
+        assert_eq!(day.part_one().unwrap(), Answer::Int(10));
+    }
+
     #[test]
     fn test_part_one() {
         let day = Day18::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        assert_eq!(day.part_one().unwrap(), Answer::Int(64));
     }
 
     #[test]
     fn test_part_two() {
         let day = Day18::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(day.part_two().unwrap(), Answer::Int(58));
     }
 }
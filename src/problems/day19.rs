@@ -1,34 +1,233 @@
-use std::io::Read;
+use std::{io::Read, str::FromStr};
 
-use super::{solutions::parse_lines, Solver};
+use anyhow::{anyhow, bail, Context};
 
-pub struct Day19(Vec<i64>);
+use super::{registry::register, solutions::parse_from_read, Answer, Solver};
 
-impl Day19 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
+
+/// Amounts of ore, clay, obsidian, and geodes, in that order
+type Resources = [i64; 4];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Blueprint {
+    id: i64,
+    // costs[robot] is what it takes to build that robot
+    costs: [Resources; 4],
+}
+
+impl FromStr for Blueprint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. ...
+        let s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+        let rest = s
+            .strip_prefix("Blueprint ")
+            .ok_or_else(|| anyhow!("Expected 'Blueprint', got '{s}'"))?;
+        let (id, rest) = rest
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Expected ':' in '{s}'"))?;
+
+        let mut costs = [[0; 4]; 4];
+        let mut seen = [false; 4];
+        for sentence in rest.split('.').map(str::trim).filter(|p| !p.is_empty()) {
+            let (robot, cost) = sentence
+                .strip_prefix("Each ")
+                .and_then(|p| p.split_once(" robot costs "))
+                .ok_or_else(|| anyhow!("Unexpected sentence '{sentence}'"))?;
+            let robot = resource_index(robot)?;
+            seen[robot] = true;
+
+            for part in cost.split(" and ") {
+                let (n, resource) = part
+                    .split_once(' ')
+                    .ok_or_else(|| anyhow!("Unexpected cost '{part}'"))?;
+                costs[robot][resource_index(resource)?] = n.parse()?;
+            }
+        }
+
+        if seen.contains(&false) {
+            bail!("Missing robot cost in '{s}'");
+        }
+
+        Ok(Blueprint {
+            id: id.parse()?,
+            costs,
+        })
+    }
+}
+
+fn resource_index(name: &str) -> anyhow::Result<usize> {
+    Ok(match name {
+        "ore" => ORE,
+        "clay" => CLAY,
+        "obsidian" => OBSIDIAN,
+        "geode" => GEODE,
+        _ => bail!("Unknown resource '{name}'"),
+    })
+}
+
+impl Blueprint {
+    /// The most geodes that can be opened in `minutes`, starting with a single
+    /// ore robot
+    pub fn max_geodes(&self, minutes: i64) -> i64 {
+        // There's no point having more robots of a type than we could spend
+        // in a single minute, as we can only build one robot at a time
+        let mut max_robots = [i64::MAX; 4];
+        for (r, max) in max_robots.iter_mut().enumerate().take(GEODE) {
+            *max = self.costs.iter().map(|c| c[r]).max().unwrap_or(0);
+        }
+
+        let mut search = Search {
+            blueprint: self,
+            max_robots,
+            best: 0,
+        };
+        search.run(minutes, [1, 0, 0, 0], [0; 4], 0);
+        search.best
+    }
+
+    pub fn quality_level(&self, minutes: i64) -> i64 {
+        self.id * self.max_geodes(minutes)
+    }
+}
+
+struct Search<'a> {
+    blueprint: &'a Blueprint,
+    max_robots: [i64; 4],
+    best: i64,
+}
+
+impl Search<'_> {
+    // Depth-first over which robot to build next. Rather than stepping minute
+    // by minute, we skip straight to the minute that robot is affordable.
+    //
+    // Geodes are counted up front: a geode robot built with t minutes left
+    // will open t geodes.
+    fn run(&mut self, remaining: i64, robots: [i64; 4], stock: Resources, geodes: i64) {
+        self.best = self.best.max(geodes);
+
+        // Even building a geode robot every remaining minute can't beat the
+        // best, so give up on this branch
+        let optimistic = geodes + remaining * (remaining - 1) / 2;
+        if optimistic <= self.best {
+            return;
+        }
+
+        // Try geode robots first, as they tend to find good answers quickly
+        for robot in (0..4).rev() {
+            if robots[robot] >= self.max_robots[robot] {
+                continue;
+            }
+            let cost = self.blueprint.costs[robot];
+
+            // Minutes until we can afford it, if ever
+            let mut wait = 0;
+            let mut possible = true;
+            for r in 0..3 {
+                let needed = cost[r] - stock[r];
+                if needed <= 0 {
+                    continue;
+                }
+                if robots[r] == 0 {
+                    possible = false;
+                    break;
+                }
+                wait = wait.max((needed + robots[r] - 1) / robots[r]);
+            }
+            if !possible {
+                continue;
+            }
+
+            // Wait, then a minute to build
+            let left = remaining - wait - 1;
+            if left <= 0 {
+                continue;
+            }
+
+            let mut next_stock = stock;
+            for r in 0..4 {
+                next_stock[r] += robots[r] * (wait + 1) - cost[r];
+            }
+
+            if robot == GEODE {
+                self.run(left, robots, next_stock, geodes + left);
+            } else {
+                let mut next_robots = robots;
+                next_robots[robot] += 1;
+                self.run(left, next_robots, next_stock, geodes);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blueprints(Vec<Blueprint>);
+
+impl FromStr for Blueprints {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Blueprints may be wrapped over several lines, so split on the
+        // keyword rather than by line
+        let blueprints = s
+            .split("Blueprint")
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                format!("Blueprint {p}")
+                    .parse()
+                    .with_context(|| format!("Parsing blueprint '{p}'"))
+            })
+            .collect::<anyhow::Result<Vec<Blueprint>>>()?;
+
+        Ok(Blueprints(blueprints))
     }
+}
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+impl Blueprints {
+    /// Evaluate `f` on every blueprint, one thread per blueprint. A thread
+    /// that panics makes this an error, once all the others have finished.
+    pub fn par_map<T: Send>(&self, f: impl Fn(&Blueprint) -> T + Sync) -> anyhow::Result<Vec<T>> {
+        let f = &f;
+        let results: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = self.0.iter().map(|bp| scope.spawn(move || f(bp))).collect();
+            handles.into_iter().map(|h| h.join()).collect()
+        });
+        results
+            .into_iter()
+            .zip(&self.0)
+            .map(|(result, bp)| {
+                result.map_err(|_| anyhow!("The thread for blueprint {} panicked", bp.id))
+            })
+            .collect()
     }
 }
 
+pub struct Day19(Blueprints);
+
+register!(19, Day19, "Not Enough Minerals");
+
 impl Solver for Day19 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day19(items))
+        let blueprints = parse_from_read(input)?;
+        Ok(Day19(blueprints))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let sum: i64 = self.0.par_map(|bp| bp.quality_level(24))?.iter().sum();
+        Ok(sum.into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let Day19(Blueprints(all)) = self;
+        let first = Blueprints(all.iter().take(3).cloned().collect());
+        let product: i64 = first.par_map(|bp| bp.max_geodes(32))?.iter().product();
+        Ok(product.into())
     }
 }
 
@@ -41,20 +240,61 @@ mod tests {
     use super::*;
 
     const EXAMPLE: &str = r"
-        1
-        9
-        4
+        Blueprint 1:
+          Each ore robot costs 4 ore.
+          Each clay robot costs 2 ore.
+          Each obsidian robot costs 3 ore and 14 clay.
+          Each geode robot costs 2 ore and 7 obsidian.
+
+        Blueprint 2:
+          Each ore robot costs 2 ore.
+          Each clay robot costs 3 ore.
+          Each obsidian robot costs 3 ore and 8 clay.
+          Each geode robot costs 3 ore and 12 obsidian.
     ";
 
+    fn example() -> Blueprints {
+        unindented(EXAMPLE).unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let bps = example();
+        assert_eq!(bps.0.len(), 2);
+        assert_eq!(
+            bps.0[0],
+            Blueprint {
+                id: 1,
+                costs: [[4, 0, 0, 0], [2, 0, 0, 0], [3, 14, 0, 0], [2, 0, 7, 0]],
+            }
+        );
+
+        // The real input has one blueprint per line
+        let line = "Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. \
+                    Each obsidian robot costs 3 ore and 8 clay. \
+                    Each geode robot costs 3 ore and 12 obsidian.";
+        let bps: Blueprints = line.parse().unwrap();
+        assert_eq!(bps.0, vec![example().0[1].clone()]);
+    }
+
+    #[test]
+    fn test_max_geodes() {
+        let bps = example();
+        assert_eq!(bps.par_map(|bp| bp.max_geodes(24)).unwrap(), vec![9, 12]);
+        assert_eq!(bps.par_map(|bp| bp.max_geodes(32)).unwrap(), vec![56, 62]);
+        let panicked = bps.par_map(|bp| if bp.id == 2 { panic!("Oops") } else { bp.id });
+        assert!(panicked.is_err());
+    }
+
     #[test]
     fn test_part_one() {
         let day = Day19::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        assert_eq!(day.part_one().unwrap(), Answer::Int(33));
     }
 
     #[test]
     fn test_part_two() {
         let day = Day19::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(day.part_two().unwrap(), Answer::Int(3472));
     }
 }
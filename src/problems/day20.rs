@@ -1,34 +1,157 @@
 use std::io::Read;
 
-use super::{solutions::parse_lines, Solver};
+use anyhow::anyhow;
 
+use super::{registry::register, solutions::parse_lines, Answer, Solver};
+
+const DECRYPTION_KEY: i64 = 811_589_153;
+
+/// A list of the items `0..n` in some order, split into blocks of roughly
+/// √n items each.
+///
+/// Finding, removing, and inserting an item each only touch one block plus
+/// the list of block lengths, so they're O(√n) rather than the O(n) of a
+/// plain `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockList {
+    blocks: Vec<Vec<usize>>,
+    // block_of[item] is the block currently holding that item
+    block_of: Vec<usize>,
+    block_size: usize,
+}
+
+impl BlockList {
+    pub fn new(len: usize) -> Self {
+        let block_size = (len as f64).sqrt().ceil().max(1.0) as usize;
+        let mut list = BlockList {
+            blocks: vec![(0..len).collect()],
+            block_of: vec![0; len],
+            block_size,
+        };
+        list.rebuild();
+        list
+    }
+
+    // Re-split everything into evenly sized blocks
+    fn rebuild(&mut self) {
+        let items: Vec<usize> = self.blocks.drain(..).flatten().collect();
+        self.blocks = items
+            .chunks(self.block_size)
+            .map(<[usize]>::to_vec)
+            .collect();
+        if self.blocks.is_empty() {
+            self.blocks.push(Vec::new());
+        }
+        for (b, block) in self.blocks.iter().enumerate() {
+            for &item in block {
+                self.block_of[item] = b;
+            }
+        }
+    }
+
+    /// The current position of `item` in the list
+    pub fn index_of(&self, item: usize) -> usize {
+        let b = self.block_of[item];
+        let before: usize = self.blocks[..b].iter().map(Vec::len).sum();
+        let within = self.blocks[b]
+            .iter()
+            .position(|&i| i == item)
+            .expect("block_of out of sync");
+        before + within
+    }
+
+    /// Remove `item`, returning the position it was at
+    pub fn remove(&mut self, item: usize) -> usize {
+        let index = self.index_of(item);
+        let block = &mut self.blocks[self.block_of[item]];
+        let within = block.iter().position(|&i| i == item).unwrap();
+        block.remove(within);
+        index
+    }
+
+    /// Insert `item` so that it ends up at position `index`
+    pub fn insert(&mut self, mut index: usize, item: usize) {
+        let last = self.blocks.len() - 1;
+        let mut b = 0;
+        // Prefer the end of a block to the start of the next one, so that
+        // inserting at the very end works too
+        while b < last && index > self.blocks[b].len() {
+            index -= self.blocks[b].len();
+            b += 1;
+        }
+        assert!(index <= self.blocks[b].len(), "Index out of range");
+
+        self.blocks[b].insert(index, item);
+        self.block_of[item] = b;
+        if self.blocks[b].len() > 2 * self.block_size {
+            self.rebuild();
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks.iter().flatten().copied()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Day20(Vec<i64>);
 
+register!(20, Day20, "Grove Positioning System");
+
 impl Day20 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+    /// Mix the numbers `rounds` times, after multiplying each by `key`.
+    /// Returns the mixed numbers, in order.
+    pub fn mix(&self, key: i64, rounds: usize) -> Vec<i64> {
+        let values: Vec<i64> = self.0.iter().map(|&v| v * key).collect();
+        let n = values.len();
+        let mut list = BlockList::new(n);
+        if n <= 1 {
+            return values;
+        }
+
+        // Moving around the whole circle, minus the item itself, ends up
+        // back in the same place
+        let cycle = (n - 1) as i64;
+        for _ in 0..rounds {
+            for (item, &v) in values.iter().enumerate() {
+                let index = list.remove(item) as i64;
+                let new_index = (index + v).rem_euclid(cycle);
+                list.insert(new_index as usize, item);
+            }
+        }
+
+        list.iter().map(|item| values[item]).collect()
     }
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+    /// The sum of the numbers 1000, 2000, and 3000 after the 0
+    pub fn grove_coordinates(mixed: &[i64]) -> Option<i64> {
+        let zero = mixed.iter().position(|&v| v == 0)?;
+        let n = mixed.len();
+        Some(
+            [1000, 2000, 3000]
+                .iter()
+                .map(|i| mixed[(zero + i) % n])
+                .sum(),
+        )
     }
 }
 
 impl Solver for Day20 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day20(items))
+        let numbers = parse_lines::<i64>(input)?;
+        Ok(Day20(numbers))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let mixed = self.mix(1, 1);
+        let sum = Day20::grove_coordinates(&mixed).ok_or_else(|| anyhow!("No zero in input"))?;
+        Ok(sum.into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let mixed = self.mix(DECRYPTION_KEY, 10);
+        let sum = Day20::grove_coordinates(&mixed).ok_or_else(|| anyhow!("No zero in input"))?;
+        Ok(sum.into())
     }
 }
 
@@ -42,19 +165,75 @@ mod tests {
 
     const EXAMPLE: &str = r"
         1
-        9
+        2
+        -3
+        3
+        -2
+        0
         4
     ";
 
+    // Rotate so that the 0 is first, as the mix is circular
+    fn from_zero(mixed: &[i64]) -> Vec<i64> {
+        let zero = mixed.iter().position(|&v| v == 0).unwrap();
+        mixed[zero..]
+            .iter()
+            .chain(&mixed[..zero])
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn test_block_list() {
+        let mut list = BlockList::new(10);
+        assert_eq!(list.iter().collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+
+        assert_eq!(list.remove(3), 3);
+        list.insert(9, 3);
+        assert_eq!(list.index_of(3), 9);
+        assert_eq!(list.index_of(4), 3);
+
+        // Force a rebuild by piling everything into one block
+        for item in 0..10 {
+            list.remove(item);
+            list.insert(0, item);
+        }
+        assert_eq!(
+            list.iter().collect::<Vec<_>>(),
+            (0..10).rev().collect::<Vec<_>>()
+        );
+        for item in 0..10 {
+            assert_eq!(list.index_of(item), 9 - item);
+        }
+    }
+
+    #[test]
+    fn test_mix() {
+        let day = Day20::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
+        assert_eq!(from_zero(&day.mix(1, 1)), vec![0, 3, -2, 1, 2, -3, 4]);
+        assert_eq!(
+            from_zero(&day.mix(DECRYPTION_KEY, 10)),
+            vec![
+                0,
+                -2434767459,
+                1623178306,
+                3246356612,
+                -1623178306,
+                2434767459,
+                811589153
+            ]
+        );
+    }
+
     #[test]
     fn test_part_one() {
         let day = Day20::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        assert_eq!(day.part_one().unwrap(), Answer::Int(3));
     }
 
     #[test]
     fn test_part_two() {
         let day = Day20::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(day.part_two().unwrap(), Answer::Int(1623178306));
     }
 }
@@ -1,34 +1,357 @@
-use std::io::Read;
+use std::{
+    collections::HashMap,
+    fmt,
+    io::Read,
+    ops::{Add, Mul, Sub},
+    str::FromStr,
+};
 
-use super::{solutions::parse_lines, Solver};
+use anyhow::{anyhow, bail};
 
-pub struct Day21(Vec<i64>);
+use super::{registry::register, solutions::parse_lines, Answer, Solver};
 
-impl Day21 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// An exact fraction, always kept in lowest terms with a positive denominator
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    num: i128,
+    den: i128,
+}
+
+impl Ratio {
+    /// A fraction in lowest terms. Only for denominators known not to be
+    /// zero: anything that could divide by zero goes through [`Ratio::div`].
+    fn new(num: i128, den: i128) -> Self {
+        debug_assert!(den != 0, "Division by zero");
+        let g = gcd(num, den) * den.signum();
+        Ratio {
+            num: num / g,
+            den: den / g,
+        }
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.num == 0
+    }
+
+    /// Divide, failing rather than panicking when `rhs` is zero
+    pub fn div(self, rhs: Ratio) -> anyhow::Result<Ratio> {
+        if rhs.is_zero() {
+            bail!("Division by zero in {self} / {rhs}");
+        }
+        Ok(Ratio::new(self.num * rhs.den, self.den * rhs.num))
+    }
+
+    pub fn to_integer(self) -> Option<i128> {
+        (self.den == 1).then_some(self.num)
+    }
+}
+
+impl From<i64> for Ratio {
+    fn from(n: i64) -> Self {
+        Ratio::new(n as i128, 1)
+    }
+}
+
+impl Add for Ratio {
+    type Output = Ratio;
+
+    fn add(self, rhs: Ratio) -> Ratio {
+        Ratio::new(self.num * rhs.den + rhs.num * self.den, self.den * rhs.den)
+    }
+}
+
+impl Sub for Ratio {
+    type Output = Ratio;
+
+    fn sub(self, rhs: Ratio) -> Ratio {
+        Ratio::new(self.num * rhs.den - rhs.num * self.den, self.den * rhs.den)
+    }
+}
+
+impl Mul for Ratio {
+    type Output = Ratio;
+
+    fn mul(self, rhs: Ratio) -> Ratio {
+        Ratio::new(self.num * rhs.num, self.den * rhs.den)
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_integer() {
+            Some(n) => write!(f, "{n}"),
+            None => write!(f, "{}/{}", self.num, self.den),
+        }
+    }
+}
+
+impl From<Ratio> for Answer {
+    fn from(r: Ratio) -> Self {
+        match r.to_integer() {
+            Some(n) => n.into(),
+            None => r.to_string().into(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl FromStr for Operation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "+" => Operation::Add,
+            "-" => Operation::Sub,
+            "*" => Operation::Mul,
+            "/" => Operation::Div,
+            _ => bail!("Unknown operation '{s}'"),
+        })
+    }
+}
+
+impl Operation {
+    fn apply(self, l: Ratio, r: Ratio) -> anyhow::Result<Ratio> {
+        Ok(match self {
+            Operation::Add => l + r,
+            Operation::Sub => l - r,
+            Operation::Mul => l * r,
+            Operation::Div => l.div(r)?,
+        })
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Operation::Add => "+",
+            Operation::Sub => "-",
+            Operation::Mul => "*",
+            Operation::Div => "/",
+        };
+        f.write_str(c)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Job {
+    Number(i64),
+    Math(String, Operation, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Monkey {
+    name: String,
+    job: Job,
+}
+
+impl FromStr for Monkey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, job) = s
+            .split_once(": ")
+            .ok_or_else(|| anyhow!("Expected 'name: job', got '{s}'"))?;
+
+        let parts: Vec<&str> = job.split_whitespace().collect();
+        let job = match parts.as_slice() {
+            [n] => Job::Number(n.parse()?),
+            [l, op, r] => Job::Math(l.to_string(), op.parse()?, r.to_string()),
+            _ => bail!("Unexpected job '{job}'"),
+        };
+
+        Ok(Monkey {
+            name: name.to_owned(),
+            job,
+        })
+    }
+}
+
+/// An expression tree, with every subtree not depending on the human folded
+/// down to a number
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Number(Ratio),
+    Human,
+    Math(Box<Expr>, Operation, Box<Expr>),
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{n}"),
+            Expr::Human => f.write_str(HUMAN),
+            Expr::Math(l, op, r) => write!(f, "({l} {op} {r})"),
+        }
+    }
+}
+
+impl Expr {
+    /// Solve `self = target` for the human's value, by undoing each
+    /// operation on the way down to the human
+    pub fn solve(&self, target: Ratio) -> anyhow::Result<Ratio> {
+        match self {
+            Expr::Human => Ok(target),
+            Expr::Number(n) => bail!("No unknown to solve for in {n} = {target}"),
+            Expr::Math(l, op, r) => match (l.as_ref(), r.as_ref()) {
+                (unknown, &Expr::Number(c)) => {
+                    // unknown op c = target
+                    let t = match op {
+                        Operation::Add => target - c,
+                        Operation::Sub => target + c,
+                        Operation::Mul => target.div(c)?,
+                        Operation::Div if c.is_zero() => bail!("Division by zero in {self}"),
+                        Operation::Div => target * c,
+                    };
+                    unknown.solve(t)
+                }
+                (&Expr::Number(c), unknown) => {
+                    // c op unknown = target
+                    let t = match op {
+                        Operation::Add => target - c,
+                        Operation::Sub => c - target,
+                        Operation::Mul => target.div(c)?,
+                        Operation::Div => c.div(target)?,
+                    };
+                    unknown.solve(t)
+                }
+                _ => bail!("The unknown appears on both sides of {self}"),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Troop(HashMap<String, Job>);
+
+impl Troop {
+    pub fn new(monkeys: Vec<Monkey>) -> anyhow::Result<Self> {
+        let mut jobs = HashMap::new();
+        for Monkey { name, job } in monkeys {
+            if jobs.insert(name.clone(), job).is_some() {
+                bail!("Duplicate monkey {name}");
+            }
+        }
+        let troop = Troop(jobs);
+        let mut done = HashMap::new();
+        for name in troop.0.keys() {
+            troop.check_cycles(name, &mut done)?;
+        }
+        Ok(troop)
+    }
+
+    /// Make sure no monkey waits, however indirectly, on itself, or we'd
+    /// never stop evaluating it. `done` maps each monkey seen to whether we've
+    /// finished with it, rather than still following what it waits on.
+    fn check_cycles<'a>(
+        &'a self,
+        name: &'a str,
+        done: &mut HashMap<&'a str, bool>,
+    ) -> anyhow::Result<()> {
+        match done.get(name) {
+            Some(true) => return Ok(()),
+            Some(false) => bail!("Monkey {name} waits on itself"),
+            None => {}
+        }
+        done.insert(name, false);
+        if let Some(Job::Math(l, _, r)) = self.0.get(name) {
+            self.check_cycles(l, done)?;
+            self.check_cycles(r, done)?;
+        }
+        done.insert(name, true);
+        Ok(())
+    }
+
+    fn job(&self, name: &str) -> anyhow::Result<&Job> {
+        self.0
+            .get(name)
+            .ok_or_else(|| anyhow!("Unknown monkey {name}"))
+    }
+
+    /// What the monkey `name` yells
+    pub fn evaluate(&self, name: &str) -> anyhow::Result<Ratio> {
+        match self.job(name)? {
+            &Job::Number(n) => Ok(n.into()),
+            Job::Math(l, op, r) => op.apply(self.evaluate(l)?, self.evaluate(r)?),
+        }
+    }
+
+    /// The reduced expression for what `name` yells, with the human as the
+    /// unknown
+    pub fn expression(&self, name: &str) -> anyhow::Result<Expr> {
+        if name == HUMAN {
+            return Ok(Expr::Human);
+        }
+        match self.job(name)? {
+            &Job::Number(n) => Ok(Expr::Number(n.into())),
+            Job::Math(l, op, r) => {
+                let (l, r) = (self.expression(l)?, self.expression(r)?);
+                Ok(match (l, r) {
+                    (Expr::Number(l), Expr::Number(r)) => Expr::Number(op.apply(l, r)?),
+                    (l, r) => Expr::Math(Box::new(l), *op, Box::new(r)),
+                })
+            }
+        }
     }
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+    /// The two sides that the root compares, as reduced expressions
+    pub fn equation(&self) -> anyhow::Result<(Expr, Expr)> {
+        match self.job(ROOT)? {
+            Job::Number(_) => bail!("Root has no equation"),
+            Job::Math(l, _, r) => Ok((self.expression(l)?, self.expression(r)?)),
+        }
+    }
+
+    /// The number the human must yell for both sides of the root to match
+    pub fn solve_human(&self) -> anyhow::Result<Ratio> {
+        match self.equation()? {
+            (Expr::Number(target), unknown) | (unknown, Expr::Number(target)) => {
+                unknown.solve(target)
+            }
+            (l, r) => bail!("The unknown appears on both sides of {l} = {r}"),
+        }
     }
 }
 
+pub struct Day21(Troop);
+
+register!(21, Day21, "Monkey Math");
+
 impl Solver for Day21 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day21(items))
+        let monkeys = parse_lines::<Monkey>(input)?;
+        Ok(Day21(Troop::new(monkeys)?))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let value = self.0.evaluate(ROOT)?;
+        Ok(value.into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        if log::log_enabled!(log::Level::Info) {
+            let (l, r) = self.0.equation()?;
+            log::info!("Equation: {l} = {r}");
+        }
+        let value = self.0.solve_human()?;
+        Ok(value.into())
     }
 }
 
@@ -41,20 +364,124 @@ mod tests {
     use super::*;
 
     const EXAMPLE: &str = r"
-        1
-        9
-        4
+        root: pppw + sjmn
+        dbpl: 5
+        cczh: sllz + lgvd
+        zczc: 2
+        ptdq: humn - dvpt
+        dvpt: 3
+        lfqf: 4
+        humn: 5
+        ljgn: 2
+        sjmn: drzm * dbpl
+        sllz: 4
+        pppw: cczh / lfqf
+        lgvd: ljgn * ptdq
+        drzm: hmdt - zczc
+        hmdt: 32
     ";
 
+    fn example() -> Troop {
+        let monkeys = parse_lines::<Monkey>(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
+        Troop::new(monkeys).unwrap()
+    }
+
+    #[test]
+    fn test_ratio() {
+        let half = Ratio::new(1, 2);
+        assert_eq!(half, Ratio::new(-3, -6));
+        assert_eq!(half + half, Ratio::from(1));
+        assert_eq!((half - Ratio::from(1)).to_string(), "-1/2");
+        assert_eq!(Ratio::from(3).div(Ratio::from(6)).unwrap(), half);
+        assert_eq!((half * Ratio::from(4)).to_integer(), Some(2));
+    }
+
+    #[test]
+    fn test_zero_divisor() {
+        assert!(Ratio::from(3).div(Ratio::from(0)).is_err());
+
+        let troop = |lines: &str| {
+            let monkeys = parse_lines::<Monkey>(unindented(lines).unwrap().as_bytes()).unwrap();
+            Troop::new(monkeys).unwrap()
+        };
+        let divided = troop(
+            r"
+            root: humn / zero
+            humn: 5
+            zero: 0
+            ",
+        );
+        assert!(divided.evaluate(ROOT).is_err());
+
+        // Nothing times zero can make 3
+        let times_zero = troop(
+            r"
+            root: prod + three
+            prod: humn * zero
+            humn: 5
+            zero: 0
+            three: 3
+            ",
+        );
+        assert!(times_zero.solve_human().is_err());
+
+        let over_zero = troop(
+            r"
+            root: quot + three
+            quot: humn / zero
+            humn: 5
+            zero: 0
+            three: 3
+            ",
+        );
+        assert!(over_zero.solve_human().is_err());
+    }
+
+    #[test]
+    fn test_cycle() {
+        let monkeys = parse_lines::<Monkey>(
+            unindented(
+                r"
+                root: a + b
+                a: root + b
+                b: 1
+                humn: 3
+                ",
+            )
+            .unwrap()
+            .as_bytes(),
+        )
+        .unwrap();
+        assert!(Troop::new(monkeys).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let troop = example();
+        assert_eq!(troop.0.len(), 15);
+        assert_eq!(troop.0["dbpl"], Job::Number(5));
+        assert_eq!(
+            troop.0["root"],
+            Job::Math("pppw".to_owned(), Operation::Add, "sjmn".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_equation() {
+        let (l, r) = example().equation().unwrap();
+        assert_eq!(l.to_string(), "((4 + (2 * (humn - 3))) / 4)");
+        assert_eq!(r.to_string(), "150");
+    }
+
     #[test]
     fn test_part_one() {
         let day = Day21::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        assert_eq!(day.part_one().unwrap(), Answer::Int(152));
     }
 
     #[test]
     fn test_part_two() {
         let day = Day21::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(day.part_two().unwrap(), Answer::Int(301));
     }
 }
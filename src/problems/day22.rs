@@ -1,34 +1,400 @@
-use std::io::Read;
+use std::{
+    collections::{HashMap, VecDeque},
+    io::Read,
+    str::FromStr,
+};
 
-use super::{solutions::parse_lines, Solver};
+use adventofcode2022::{Compass, Position, Turn};
+use anyhow::{anyhow, bail};
 
-pub struct Day22(Vec<i64>);
+use super::{registry::register, solutions::parse_from_read, Answer, Solver};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Tile {
+    Open,
+    Wall,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Step {
+    Forward(usize),
+    Turn(Turn),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path(Vec<Step>);
+
+impl FromStr for Path {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = Vec::new();
+        let mut rest = s.trim();
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if digits > 0 {
+                let (n, r) = rest.split_at(digits);
+                steps.push(Step::Forward(n.parse()?));
+                rest = r;
+                continue;
+            }
+
+            let turn = match rest.as_bytes()[0] {
+                b'L' => Turn::Left,
+                b'R' => Turn::Right,
+                _ => bail!("Unexpected path instruction in '{rest}'"),
+            };
+            steps.push(Step::Turn(turn));
+            rest = &rest[1..];
+        }
+
+        Ok(Path(steps))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    rows: Vec<Vec<Option<Tile>>>,
+}
+
+impl FromStr for Board {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|c| match c {
+                        ' ' => Ok(None),
+                        '.' => Ok(Some(Tile::Open)),
+                        '#' => Ok(Some(Tile::Wall)),
+                        _ => bail!("Unexpected board character '{c}'"),
+                    })
+                    .collect()
+            })
+            .collect::<anyhow::Result<Vec<Vec<Option<Tile>>>>>()?;
+
+        if rows.iter().all(|r| r.iter().all(Option::is_none)) {
+            bail!("Empty board");
+        }
+
+        Ok(Board { rows })
+    }
+}
+
+impl Board {
+    pub fn get(&self, Position(x, y): Position) -> Option<Tile> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        *self.rows.get(y as usize)?.get(x as usize)?
+    }
+
+    pub fn start(&self) -> Position {
+        let x = self.rows[0]
+            .iter()
+            .position(|&t| t == Some(Tile::Open))
+            .unwrap_or(0);
+        Position(x as i64, 0)
+    }
+
+    fn width(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    fn tile_count(&self) -> usize {
+        self.rows.iter().flatten().filter(|t| t.is_some()).count()
+    }
+
+    /// Where you end up walking off the edge of the board, if the board wraps
+    /// around as a flat torus
+    fn wrap_flat(&self, pos: Position, dir: Compass) -> (Position, Compass) {
+        let back = dir + Turn::Reverse;
+        let mut pos = pos;
+        while self.get(pos + back).is_some() {
+            pos = pos + back;
+        }
+        (pos, dir)
+    }
+}
+
+/// 3D integer vectors, for folding the cube
+type Vec3 = [i64; 3];
+
+fn dot(a: Vec3, b: Vec3) -> i64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn neg(a: Vec3) -> Vec3 {
+    a.map(|x| -x)
+}
+
+fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(a: Vec3, n: i64) -> Vec3 {
+    a.map(|x| x * n)
+}
+
+/// How a face of the net sits on the cube: the outward normal, and the 3D
+/// directions that East and South on the board point in
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct Frame {
+    normal: Vec3,
+    east: Vec3,
+    south: Vec3,
+}
+
+impl Frame {
+    fn direction(&self, dir: Compass) -> Vec3 {
+        match dir {
+            Compass::East => self.east,
+            Compass::South => self.south,
+            Compass::West => neg(self.east),
+            Compass::North => neg(self.south),
+        }
+    }
+
+    fn compass(&self, v: Vec3) -> Option<Compass> {
+        Compass::all()
+            .into_iter()
+            .find(|&dir| self.direction(dir) == v)
+    }
+
+    /// The frame of the face next door in the net, after folding over the
+    /// shared edge
+    fn fold(&self, dir: Compass) -> Frame {
+        let Frame {
+            normal,
+            east,
+            south,
+        } = *self;
+        match dir {
+            Compass::East => Frame {
+                normal: east,
+                east: neg(normal),
+                south,
+            },
+            Compass::West => Frame {
+                normal: neg(east),
+                east: normal,
+                south,
+            },
+            Compass::South => Frame {
+                normal: south,
+                east,
+                south: neg(normal),
+            },
+            Compass::North => Frame {
+                normal: neg(south),
+                east,
+                south: normal,
+            },
+        }
+    }
+}
+
+/// The board folded up into a cube.
+///
+/// Which edges join up is worked out by walking the net and folding each
+/// face over onto its neighbours, so it works for any net shape.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cube {
+    size: i64,
+    // Face (column, row) in the net -> its frame
+    faces: HashMap<(i64, i64), Frame>,
+    // Outward normal -> face (column, row)
+    by_normal: HashMap<Vec3, (i64, i64)>,
+}
+
+impl Cube {
+    pub fn new(board: &Board) -> anyhow::Result<Self> {
+        let tiles = board.tile_count();
+        let size = ((tiles / 6) as f64).sqrt().round() as i64;
+        if size == 0 || 6 * size * size != tiles as i64 {
+            bail!("{tiles} tiles can't be folded into a cube");
+        }
+
+        let cols = board.width() as i64 / size;
+        let rows = board.rows.len() as i64 / size;
+        let is_face = |(fx, fy): (i64, i64)| {
+            (0..cols).contains(&fx)
+                && (0..rows).contains(&fy)
+                && board.get(Position(fx * size, fy * size)).is_some()
+        };
+
+        let first = (0..rows)
+            .flat_map(|fy| (0..cols).map(move |fx| (fx, fy)))
+            .find(|&f| is_face(f))
+            .ok_or_else(|| anyhow!("No faces found"))?;
+
+        let mut faces = HashMap::new();
+        faces.insert(
+            first,
+            Frame {
+                normal: [0, 0, -1],
+                east: [1, 0, 0],
+                south: [0, 1, 0],
+            },
+        );
+        let mut queue = VecDeque::from([first]);
+        while let Some(face) = queue.pop_front() {
+            let frame = faces[&face];
+            for dir in Compass::all() {
+                let (dx, dy) = dir.into();
+                let next = (face.0 + dx, face.1 + dy);
+                if is_face(next) && !faces.contains_key(&next) {
+                    faces.insert(next, frame.fold(dir));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let by_normal: HashMap<Vec3, (i64, i64)> =
+            faces.iter().map(|(&face, f)| (f.normal, face)).collect();
+        if faces.len() != 6 || by_normal.len() != 6 {
+            bail!("The board is not a cube net");
+        }
+
+        Ok(Cube {
+            size,
+            faces,
+            by_normal,
+        })
+    }
+
+    /// Where you end up walking off the edge of a face, and which way you're
+    /// then facing.
+    ///
+    /// Each tile's centre is placed on the surface of a cube running from -n
+    /// to n (doubling the coordinates keeps tile centres on integers). Walking
+    /// off an edge moves you one half-tile further in the direction of
+    /// travel, and one half-tile down into the cube, which lands on the
+    /// centre of the tile across the edge.
+    fn wrap(&self, pos: Position, dir: Compass) -> (Position, Compass) {
+        let n = self.size;
+        let Position(x, y) = pos;
+        let from = &self.faces[&(x.div_euclid(n), y.div_euclid(n))];
+        let (i, j) = (x.rem_euclid(n), y.rem_euclid(n));
+
+        let center = add(
+            scale(from.normal, n),
+            add(
+                scale(from.east, 2 * i + 1 - n),
+                scale(from.south, 2 * j + 1 - n),
+            ),
+        );
+        let travel = from.direction(dir);
+        let target = add(add(center, travel), neg(from.normal));
+
+        let face = self.by_normal[&travel];
+        let to = &self.faces[&face];
+        let i = (dot(target, to.east) + n - 1) / 2;
+        let j = (dot(target, to.south) + n - 1) / 2;
+        let dir = to
+            .compass(neg(from.normal))
+            .expect("Folded faces should share an edge");
+
+        (Position(face.0 * n + i, face.1 * n + j), dir)
+    }
+}
+
+/// How the edges of the board join up
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Wrapping {
+    Flat,
+    Cube,
+}
+
+pub struct Day22 {
+    board: Board,
+    path: Path,
+}
+
+register!(22, Day22, "Monkey Map");
 
 impl Day22 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+    /// Follow the path, returning the final position and facing
+    pub fn walk(&self, wrapping: Wrapping) -> anyhow::Result<(Position, Compass)> {
+        let cube = match wrapping {
+            Wrapping::Flat => None,
+            Wrapping::Cube => Some(Cube::new(&self.board)?),
+        };
+
+        let mut pos = self.board.start();
+        let mut dir = Compass::East;
+        for &step in &self.path.0 {
+            let n = match step {
+                Step::Turn(t) => {
+                    dir = dir + t;
+                    continue;
+                }
+                Step::Forward(n) => n,
+            };
+
+            for _ in 0..n {
+                let (next, next_dir) = match self.board.get(pos + dir) {
+                    Some(_) => (pos + dir, dir),
+                    None => match &cube {
+                        None => self.board.wrap_flat(pos, dir),
+                        Some(cube) => cube.wrap(pos, dir),
+                    },
+                };
+                if self.board.get(next) == Some(Tile::Wall) {
+                    break;
+                }
+                (pos, dir) = (next, next_dir);
+            }
+        }
+
+        Ok((pos, dir))
+    }
+
+    pub fn password(Position(x, y): Position, dir: Compass) -> i64 {
+        let facing = match dir {
+            Compass::East => 0,
+            Compass::South => 1,
+            Compass::West => 2,
+            Compass::North => 3,
+        };
+        1000 * (y + 1) + 4 * (x + 1) + facing
     }
+}
+
+impl FromStr for Day22 {
+    type Err = anyhow::Error;
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_start_matches('\n');
+        let (board, path) = s
+            .split_once("\n\n")
+            .ok_or_else(|| anyhow!("Expected a blank line between board and path"))?;
+        Ok(Day22 {
+            board: board.parse()?,
+            path: path.parse()?,
+        })
     }
 }
 
 impl Solver for Day22 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day22(items))
+        parse_from_read(input)
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let (pos, dir) = self.walk(Wrapping::Flat)?;
+        let password = Day22::password(pos, dir);
+        Ok(password.into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let (pos, dir) = self.walk(Wrapping::Cube)?;
+        let password = Day22::password(pos, dir);
+        Ok(password.into())
     }
 }
 
@@ -36,25 +402,129 @@ impl Solver for Day22 {
 mod tests {
     use test_log::test;
 
-    use crate::problems::testfns::unindented;
+    use crate::problems::testfns::unindent;
 
     use super::*;
 
     const EXAMPLE: &str = r"
-        1
-        9
-        4
+                        ...#
+                        .#..
+                        #...
+                        ....
+                ...#.......#
+                ........#...
+                ..#....#....
+                ..........#.
+                        ...#....
+                        .....#..
+                        .#......
+                        ......#.
+
+                10R5L5R10L4R5L5
     ";
 
+    fn example() -> Day22 {
+        unindent(EXAMPLE, 16).unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let day = example();
+        assert_eq!(day.board.start(), Position(8, 0));
+        assert_eq!(day.board.get(Position(11, 0)), Some(Tile::Wall));
+        assert_eq!(day.board.get(Position(0, 0)), None);
+        assert_eq!(day.path.0.len(), 13);
+        assert_eq!(day.path.0[0], Step::Forward(10));
+        assert_eq!(day.path.0[1], Step::Turn(Turn::Right));
+    }
+
+    #[test]
+    fn test_cube_wrap() {
+        let cube = Cube::new(&example().board).unwrap();
+        assert_eq!(cube.size, 4);
+
+        // The examples from the puzzle text
+        assert_eq!(
+            cube.wrap(Position(11, 5), Compass::East),
+            (Position(14, 8), Compass::South)
+        );
+        assert_eq!(
+            cube.wrap(Position(10, 11), Compass::South),
+            (Position(1, 7), Compass::North)
+        );
+        assert_eq!(
+            cube.wrap(Position(6, 4), Compass::North),
+            (Position(8, 2), Compass::East)
+        );
+
+        assert_wraps_reversibly(&example().board);
+    }
+
+    // Wrapping off every edge tile and then walking straight back should undo
+    // the wrap
+    fn assert_wraps_reversibly(board: &Board) {
+        let cube = Cube::new(board).unwrap();
+        let n = cube.size;
+        for &(fx, fy) in cube.faces.keys() {
+            for dir in Compass::all() {
+                for k in 0..n {
+                    let (x, y) = match dir {
+                        Compass::East => (n - 1, k),
+                        Compass::West => (0, k),
+                        Compass::South => (k, n - 1),
+                        Compass::North => (k, 0),
+                    };
+                    let pos = Position(fx * n + x, fy * n + y);
+                    if board.get(pos + dir).is_some() {
+                        continue;
+                    }
+                    let (there, facing) = cube.wrap(pos, dir);
+                    assert!(board.get(there).is_some());
+                    let back = facing + Turn::Reverse;
+                    assert_eq!(cube.wrap(there, back), (pos, dir + Turn::Reverse));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_other_net() {
+        // The shape of the real inputs, which differs from the example
+        let board: Board = unindent(
+            r"
+                ....
+                ....
+                ..
+                ..
+              ....
+              ....
+              ..
+              ..
+            ",
+            14,
+        )
+        .unwrap()
+        .parse()
+        .unwrap();
+        assert_wraps_reversibly(&board);
+
+        let cube = Cube::new(&board).unwrap();
+        // Off the top of the top-left face onto the left of the bottom face
+        assert_eq!(
+            cube.wrap(Position(3, 0), Compass::North),
+            (Position(0, 7), Compass::East)
+        );
+    }
+
     #[test]
     fn test_part_one() {
-        let day = Day22::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        let day = example();
+        assert_eq!(day.part_one().unwrap(), Answer::Int(6032));
     }
 
     #[test]
     fn test_part_two() {
-        let day = Day22::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        let day = example();
+        assert_eq!(day.part_two().unwrap(), Answer::Int(5031));
     }
 }
@@ -1,34 +1,175 @@
-use std::io::Read;
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    fmt,
+    io::Read,
+    str::FromStr,
+};
 
-use super::{solutions::parse_lines, Solver};
+use adventofcode2022::{Compass, Position};
+use anyhow::bail;
 
-pub struct Day23(Vec<i64>);
+use super::{registry::register, solutions::parse_from_read, Answer, Solver};
 
-impl Day23 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+// The order directions are considered in on the first round
+const ORDER: [Compass; 4] = [Compass::North, Compass::South, Compass::West, Compass::East];
+
+/// The three positions that must be free to move in the given direction:
+/// straight ahead, and the two diagonals either side
+fn ahead(pos: Position, dir: Compass) -> [Position; 3] {
+    let p = pos + dir;
+    match dir {
+        Compass::North | Compass::South => [p, p + Compass::East, p + Compass::West],
+        Compass::East | Compass::West => [p, p + Compass::North, p + Compass::South],
     }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grove {
+    // Positions can grow without bound in any direction, so just keep a set
+    elves: HashSet<Position>,
+    // Index into ORDER of the first direction to consider
+    first: usize,
+}
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+impl FromStr for Grove {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut elves = HashSet::new();
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '#' => {
+                        elves.insert(Position(x as i64, y as i64));
+                    }
+                    '.' => {}
+                    _ => bail!("Unexpected character '{c}'"),
+                }
+            }
+        }
+
+        Ok(Grove { elves, first: 0 })
     }
 }
 
+impl Grove {
+    fn is_alone(&self, pos: Position) -> bool {
+        (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+            .filter(|&d| d != (0, 0))
+            .all(|d| !self.elves.contains(&(pos + d)))
+    }
+
+    /// Run a single round, returning how many elves moved
+    pub fn round(&mut self) -> usize {
+        // Target -> the elf proposing it, or None if more than one did
+        let mut proposals: HashMap<Position, Option<Position>> = HashMap::new();
+        for &elf in &self.elves {
+            if self.is_alone(elf) {
+                continue;
+            }
+
+            let dir = (0..4)
+                .map(|i| ORDER[(self.first + i) % 4])
+                .find(|&d| ahead(elf, d).iter().all(|p| !self.elves.contains(p)));
+            let Some(dir) = dir else {
+                continue;
+            };
+
+            match proposals.entry(elf + dir) {
+                Entry::Vacant(e) => {
+                    e.insert(Some(elf));
+                }
+                Entry::Occupied(mut e) => {
+                    e.insert(None);
+                }
+            }
+        }
+
+        let mut moved = 0;
+        for (target, elf) in proposals {
+            if let Some(elf) = elf {
+                self.elves.remove(&elf);
+                self.elves.insert(target);
+                moved += 1;
+            }
+        }
+
+        self.first = (self.first + 1) % 4;
+        moved
+    }
+
+    /// The smallest rectangle containing every elf, as (min, max) corners
+    pub fn bounds(&self) -> (Position, Position) {
+        let mut iter = self.elves.iter();
+        let Some(&first) = iter.next() else {
+            return Default::default();
+        };
+        iter.fold((first, first), |(lo, hi), &Position(x, y)| {
+            (
+                Position(lo.0.min(x), lo.1.min(y)),
+                Position(hi.0.max(x), hi.1.max(y)),
+            )
+        })
+    }
+
+    /// Number of empty tiles in the bounding rectangle
+    pub fn empty_ground(&self) -> i64 {
+        if self.elves.is_empty() {
+            return 0;
+        }
+        let (lo, hi) = self.bounds();
+        let (w, h) = hi - lo;
+        (w + 1) * (h + 1) - self.elves.len() as i64
+    }
+}
+
+impl fmt::Display for Grove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (lo, hi) = self.bounds();
+        for y in lo.1..=hi.1 {
+            if y > lo.1 {
+                writeln!(f)?;
+            }
+            for x in lo.0..=hi.0 {
+                let c = if self.elves.contains(&Position(x, y)) {
+                    '#'
+                } else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct Day23(Grove);
+
+register!(23, Day23, "Unstable Diffusion");
+
 impl Solver for Day23 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day23(items))
+        let grove = parse_from_read(input)?;
+        Ok(Day23(grove))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let mut grove = self.0.clone();
+        for _ in 0..10 {
+            grove.round();
+        }
+        log::debug!("After 10 rounds:\n{grove}");
+        Ok(grove.empty_ground().into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let mut grove = self.0.clone();
+        let mut rounds = 1;
+        while grove.round() > 0 {
+            rounds += 1;
+        }
+        Ok(rounds.into())
     }
 }
 
@@ -40,21 +181,50 @@ mod tests {
 
     use super::*;
 
+    const SMALL: &str = r"
+        .....
+        ..##.
+        ..#..
+        .....
+        ..##.
+        .....
+    ";
+
     const EXAMPLE: &str = r"
-        1
-        9
-        4
+        ....#..
+        ..###.#
+        #...#.#
+        .#...##
+        #.###..
+        ##.#.##
+        .#..#..
     ";
 
+    #[test]
+    fn test_small() {
+        let mut grove: Grove = unindented(SMALL).unwrap().parse().unwrap();
+        assert_eq!(grove.elves.len(), 5);
+
+        grove.round();
+        assert_eq!(grove.to_string(), "##\n..\n#.\n.#\n#.");
+        grove.round();
+        grove.round();
+        assert_eq!(
+            grove.to_string(),
+            "..#..\n....#\n#....\n....#\n.....\n..#.."
+        );
+        assert_eq!(grove.round(), 0);
+    }
+
     #[test]
     fn test_part_one() {
         let day = Day23::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        assert_eq!(day.part_one().unwrap(), Answer::Int(110));
     }
 
     #[test]
     fn test_part_two() {
         let day = Day23::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(day.part_two().unwrap(), Answer::Int(20));
     }
 }
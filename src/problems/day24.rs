@@ -1,34 +1,194 @@
-use std::io::Read;
+use std::{collections::HashSet, io::Read, str::FromStr};
 
-use super::{solutions::parse_lines, Solver};
+use adventofcode2022::{Compass, Position};
+use anyhow::{anyhow, bail};
 
-pub struct Day24(Vec<i64>);
+use super::{registry::register, solutions::parse_from_read, Answer, Solver};
 
-impl Day24 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
     }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Valley {
+    // Size of the interior, inside the walls
+    width: i64,
+    height: i64,
+    // Where each blizzard started, by the direction it moves in. Indexed by
+    // [y][x] in interior coordinates.
+    north: Vec<Vec<bool>>,
+    south: Vec<Vec<bool>>,
+    east: Vec<Vec<bool>>,
+    west: Vec<Vec<bool>>,
+    // The gaps in the top and bottom walls
+    start: Position,
+    goal: Position,
+}
+
+impl FromStr for Valley {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.trim().lines().map(str::trim).collect();
+        if lines.len() < 3 {
+            bail!("Valley too small");
+        }
+        let (top, bottom) = (lines[0], lines[lines.len() - 1]);
+        let inner = &lines[1..lines.len() - 1];
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+        let width = top.len() as i64 - 2;
+        let height = inner.len() as i64;
+        // The blizzards wrap around the inside, which can't be empty
+        if width < 1 || height < 1 {
+            bail!("No room inside the valley walls");
+        }
+        let gap = |wall: &str| {
+            wall.find('.')
+                .map(|x| x as i64 - 1)
+                .ok_or_else(|| anyhow!("No gap in wall '{wall}'"))
+        };
+        let start = Position(gap(top)?, -1);
+        let goal = Position(gap(bottom)?, height);
+
+        let empty = vec![vec![false; width as usize]; height as usize];
+        let (mut north, mut south, mut east, mut west) =
+            (empty.clone(), empty.clone(), empty.clone(), empty);
+        for (y, line) in inner.iter().enumerate() {
+            let row = line
+                .strip_prefix('#')
+                .and_then(|l| l.strip_suffix('#'))
+                .ok_or_else(|| anyhow!("Expected walls around '{line}'"))?;
+            if row.len() as i64 != width {
+                bail!("Inconsistent row length in '{line}'");
+            }
+            for (x, c) in row.chars().enumerate() {
+                let grid = match c {
+                    '.' => continue,
+                    '^' => &mut north,
+                    'v' => &mut south,
+                    '>' => &mut east,
+                    '<' => &mut west,
+                    _ => bail!("Unexpected character '{c}'"),
+                };
+                grid[y][x] = true;
+            }
+        }
+
+        Ok(Valley {
+            width,
+            height,
+            north,
+            south,
+            east,
+            west,
+            start,
+            goal,
+        })
     }
 }
 
+impl Valley {
+    /// Blizzards return to where they started every `period` minutes
+    pub fn period(&self) -> usize {
+        let (w, h) = (self.width as usize, self.height as usize);
+        w / gcd(w, h) * h
+    }
+
+    fn in_interior(&self, Position(x, y): Position) -> bool {
+        (0..self.width).contains(&x) && (0..self.height).contains(&y)
+    }
+
+    /// Is there a blizzard at `pos` at minute `t`?
+    ///
+    /// Rather than simulating, look back along each direction to where a
+    /// blizzard would have had to start to be here now.
+    pub fn blizzard_at(&self, pos: Position, t: usize) -> bool {
+        if !self.in_interior(pos) {
+            return false;
+        }
+        let Position(x, y) = pos;
+        let t = t as i64;
+        let (w, h) = (self.width, self.height);
+        let (xu, yu) = (x as usize, y as usize);
+
+        self.east[yu][(x - t).rem_euclid(w) as usize]
+            || self.west[yu][(x + t).rem_euclid(w) as usize]
+            || self.south[(y - t).rem_euclid(h) as usize][xu]
+            || self.north[(y + t).rem_euclid(h) as usize][xu]
+    }
+
+    fn is_open(&self, pos: Position, t: usize) -> bool {
+        (pos == self.start || pos == self.goal || self.in_interior(pos))
+            && !self.blizzard_at(pos, t)
+    }
+
+    /// The earliest minute we can reach `to`, setting off from `from` at
+    /// minute `t`. Waiting in place counts as a move.
+    ///
+    /// The blizzards repeat, so a state is a position and the time modulo the
+    /// period; there's never any point revisiting one.
+    pub fn earliest(&self, from: Position, to: Position, t: usize) -> Option<usize> {
+        let period = self.period();
+        let mut seen: HashSet<(Position, usize)> = HashSet::new();
+        let mut frontier = vec![from];
+        let mut t = t;
+
+        while !frontier.is_empty() {
+            if frontier.contains(&to) {
+                return Some(t);
+            }
+
+            t += 1;
+            let mut next = Vec::new();
+            for pos in frontier {
+                let moves = Compass::all().map(|d| pos + d);
+                for p in moves.into_iter().chain(std::iter::once(pos)) {
+                    if self.is_open(p, t) && seen.insert((p, t % period)) {
+                        next.push(p);
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        None
+    }
+}
+
+pub struct Day24(Valley);
+
+register!(24, Day24, "Blizzard Basin");
+
 impl Solver for Day24 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day24(items))
+        let valley = parse_from_read(input)?;
+        Ok(Day24(valley))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let v = &self.0;
+        let t = v
+            .earliest(v.start, v.goal, 0)
+            .ok_or_else(|| anyhow!("No path to the goal"))?;
+        Ok(t.into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        let v = &self.0;
+        let there = v
+            .earliest(v.start, v.goal, 0)
+            .ok_or_else(|| anyhow!("No path to the goal"))?;
+        let back = v
+            .earliest(v.goal, v.start, there)
+            .ok_or_else(|| anyhow!("No path back to the start"))?;
+        let again = v
+            .earliest(v.start, v.goal, back)
+            .ok_or_else(|| anyhow!("No path to the goal again"))?;
+        Ok(again.into())
     }
 }
 
@@ -41,20 +201,72 @@ mod tests {
     use super::*;
 
     const EXAMPLE: &str = r"
-        1
-        9
-        4
+        #.######
+        #>>.<^<#
+        #.<..<<#
+        #>v.><>#
+        #<^v^^>#
+        ######.#
     ";
 
+    fn example() -> Valley {
+        unindented(EXAMPLE).unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let valley = example();
+        assert_eq!((valley.width, valley.height), (6, 4));
+        assert_eq!(valley.start, Position(0, -1));
+        assert_eq!(valley.goal, Position(5, 4));
+        assert_eq!(valley.period(), 12);
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        assert!("#.\n##\n#.".parse::<Valley>().is_err());
+        assert!("#.#\n#.\n#.#".parse::<Valley>().is_err());
+        assert!("..\n..\n..".parse::<Valley>().is_err());
+    }
+
+    #[test]
+    fn test_blizzards() {
+        // The single-blizzard example from the puzzle
+        let valley: Valley = unindented(
+            r"
+            #.#####
+            #.....#
+            #>....#
+            #.....#
+            #...v.#
+            #.....#
+            #####.#
+        ",
+        )
+        .unwrap()
+        .parse()
+        .unwrap();
+
+        assert!(valley.blizzard_at(Position(0, 1), 0));
+        assert!(valley.blizzard_at(Position(1, 1), 1));
+        assert!(valley.blizzard_at(Position(3, 4), 1));
+        assert!(valley.blizzard_at(Position(3, 1), 3));
+        // Both wrap around
+        assert!(valley.blizzard_at(Position(0, 1), 5));
+        assert!(valley.blizzard_at(Position(3, 0), 2));
+        assert!(valley.blizzard_at(Position(3, 3), 0));
+        assert!(!valley.blizzard_at(Position(2, 2), 0));
+    }
+
     #[test]
     fn test_part_one() {
         let day = Day24::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        assert_eq!(day.part_one().unwrap(), Answer::Int(18));
     }
 
     #[test]
     fn test_part_two() {
         let day = Day24::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(day.part_two().unwrap(), Answer::Int(54));
     }
 }
@@ -1,34 +1,189 @@
-use std::io::Read;
+use std::{fmt, io::Read, iter::Sum, ops::Add, str::FromStr};
 
-use super::{solutions::parse_lines, Solver};
+use anyhow::{anyhow, bail};
 
-pub struct Day25(Vec<i64>);
+use super::{registry::register, solutions::parse_lines, Answer, Solver};
 
-impl Day25 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
+/// A balanced base-5 number, with digits `=` (-2), `-` (-1), `0`, `1`, and `2`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Snafu {
+    // Least significant digit first, each in -2..=2, with no trailing zeros
+    digits: Vec<i8>,
+}
+
+impl Snafu {
+    fn from_digits(mut digits: Vec<i8>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Snafu { digits }
+    }
+
+    fn to_i128(&self) -> anyhow::Result<i128> {
+        let mut n: i128 = 0;
+        for &d in self.digits.iter().rev() {
+            // n * 5 + d, but as n * 4 + d + n: n * 5 alone can overflow even
+            // when the result fits, e.g. for i128::MIN
+            n = n
+                .checked_mul(4)
+                .and_then(|m| m.checked_add(d as i128))
+                .and_then(|m| m.checked_add(n))
+                .ok_or_else(|| anyhow!("SNAFU number {self} is out of range"))?;
+        }
+        Ok(n)
+    }
+}
+
+impl From<i128> for Snafu {
+    fn from(n: i128) -> Self {
+        let mut digits = Vec::new();
+        let mut n = n;
+        while n != 0 {
+            // Digits 3 and 4 become -2 and -1, carrying one to the next place
+            let (d, carry) = match n.rem_euclid(5) {
+                r @ 0..=2 => (r as i8, 0),
+                r => (r as i8 - 5, 1),
+            };
+            digits.push(d);
+            n = n.div_euclid(5) + carry;
+        }
+        Snafu::from_digits(digits)
+    }
+}
+
+impl From<i64> for Snafu {
+    fn from(n: i64) -> Self {
+        Snafu::from(n as i128)
+    }
+}
+
+impl TryFrom<&Snafu> for i128 {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &Snafu) -> Result<Self, Self::Error> {
+        s.to_i128()
     }
+}
 
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
+impl TryFrom<&Snafu> for i64 {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &Snafu) -> Result<Self, Self::Error> {
+        let n = s.to_i128()?;
+        i64::try_from(n).map_err(|_| anyhow!("SNAFU number {s} is out of range"))
     }
 }
 
+impl FromStr for Snafu {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            bail!("Empty SNAFU number");
+        }
+        let digits = s
+            .chars()
+            .rev()
+            .map(|c| {
+                Ok(match c {
+                    '=' => -2,
+                    '-' => -1,
+                    '0' => 0,
+                    '1' => 1,
+                    '2' => 2,
+                    _ => bail!("Invalid SNAFU digit '{c}' in '{s}'"),
+                })
+            })
+            .collect::<anyhow::Result<Vec<i8>>>()?;
+
+        Ok(Snafu::from_digits(digits))
+    }
+}
+
+impl fmt::Display for Snafu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.digits.is_empty() {
+            return f.write_str("0");
+        }
+        for &d in self.digits.iter().rev() {
+            let c = match d {
+                -2 => '=',
+                -1 => '-',
+                0 => '0',
+                1 => '1',
+                2 => '2',
+                _ => unreachable!("Invalid SNAFU digit {d}"),
+            };
+            write!(f, "{c}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Add for &Snafu {
+    type Output = Snafu;
+
+    /// Digit-by-digit addition, carrying in balanced base 5
+    fn add(self, rhs: &Snafu) -> Snafu {
+        let len = self.digits.len().max(rhs.digits.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry = 0;
+        for i in 0..len {
+            let a = self.digits.get(i).copied().unwrap_or(0);
+            let b = rhs.digits.get(i).copied().unwrap_or(0);
+            // In -5..=5, so a carry of at most 1 either way
+            let sum = a + b + carry;
+            carry = match sum {
+                3.. => 1,
+                ..=-3 => -1,
+                _ => 0,
+            };
+            digits.push(sum - 5 * carry);
+        }
+        digits.push(carry);
+
+        Snafu::from_digits(digits)
+    }
+}
+
+impl Add for Snafu {
+    type Output = Snafu;
+
+    fn add(self, rhs: Snafu) -> Snafu {
+        &self + &rhs
+    }
+}
+
+impl Sum for Snafu {
+    fn sum<I: Iterator<Item = Snafu>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), |acc, n| acc + n)
+    }
+}
+
+impl<'a> Sum<&'a Snafu> for Snafu {
+    fn sum<I: Iterator<Item = &'a Snafu>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), |acc, n| &acc + n)
+    }
+}
+
+pub struct Day25(Vec<Snafu>);
+
+register!(25, Day25, "Full of Hot Air");
+
 impl Solver for Day25 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
-
-        Ok(Day25(items))
+        let numbers = parse_lines::<Snafu>(input)?;
+        Ok(Day25(numbers))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
-        unimplemented!()
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        let total: Snafu = self.0.iter().sum();
+        Ok(total.to_string().into())
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
-        unimplemented!()
+    fn part_two(&self) -> anyhow::Result<Answer> {
+        bail!("Day 25 has no second part")
     }
 }
 
@@ -41,20 +196,78 @@ mod tests {
     use super::*;
 
     const EXAMPLE: &str = r"
-        1
-        9
-        4
+        1=-0-2
+        12111
+        2=0=
+        21
+        2=01
+        111
+        20012
+        112
+        1=-1=
+        1-12
+        12
+        1=
+        122
     ";
 
+    const CONVERSIONS: [(i64, &str); 15] = [
+        (0, "0"),
+        (1, "1"),
+        (2, "2"),
+        (3, "1="),
+        (4, "1-"),
+        (5, "10"),
+        (6, "11"),
+        (7, "12"),
+        (8, "2="),
+        (9, "2-"),
+        (10, "20"),
+        (15, "1=0"),
+        (20, "1-0"),
+        (2022, "1=11-2"),
+        (314159265, "1121-1110-1=0"),
+    ];
+
     #[test]
-    fn test_part_one() {
-        let day = Day25::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+    fn test_conversions() {
+        for (n, s) in CONVERSIONS {
+            let snafu: Snafu = s.parse().unwrap();
+            assert_eq!(Snafu::from(n), snafu);
+            assert_eq!(snafu.to_string(), s);
+            assert_eq!(i64::try_from(&snafu).unwrap(), n);
+            assert_eq!(i128::try_from(&snafu).unwrap(), n as i128);
+            assert_eq!(Snafu::from(-n).to_string().len(), s.len());
+        }
+
+        for n in [i64::MAX, i64::MIN] {
+            assert_eq!(i64::try_from(&Snafu::from(n)).unwrap(), n);
+        }
+        for n in [i128::MAX, i128::MIN] {
+            let big = Snafu::from(n);
+            assert!(i64::try_from(&big).is_err());
+            assert_eq!(i128::try_from(&big).unwrap(), n);
+        }
+        let huge = Snafu::from(i128::MAX) + Snafu::from(i128::MAX);
+        assert!(i128::try_from(&huge).is_err());
+
+        assert!("12a".parse::<Snafu>().is_err());
+        assert_eq!("000".parse::<Snafu>().unwrap().to_string(), "0");
+    }
+
+    #[test]
+    fn test_add() {
+        for a in -60..60i64 {
+            for b in -60..60i64 {
+                let sum = Snafu::from(a) + Snafu::from(b);
+                assert_eq!(sum, Snafu::from(a + b), "{a} + {b}");
+            }
+        }
     }
 
     #[test]
-    fn test_part_two() {
+    fn test_part_one() {
         let day = Day25::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(day.part_one().unwrap(), Answer::from("2=-1=0"));
     }
 }
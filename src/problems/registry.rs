@@ -0,0 +1,83 @@
+use std::io::Read;
+
+use anyhow::Context;
+
+use super::Solver;
+
+type Build = fn(&mut dyn Read) -> anyhow::Result<Box<dyn Solver>>;
+
+/// A day's solver, along with what we know about it. Each day adds its own
+/// with [`register!`], so nothing else needs to list them.
+pub struct Registration {
+    pub day: usize,
+    pub title: &'static str,
+    /// Which of the two parts have real solutions, rather than
+    /// `unimplemented!()`
+    pub parts: [bool; 2],
+    build: Build,
+}
+
+inventory::collect!(Registration);
+
+impl Registration {
+    pub const fn new(day: usize, title: &'static str, parts: [bool; 2], build: Build) -> Self {
+        Registration {
+            day,
+            title,
+            parts,
+            build,
+        }
+    }
+
+    /// Parse `input` into this day's solver
+    pub fn solver(&self, mut input: impl Read) -> anyhow::Result<Box<dyn Solver>> {
+        (self.build)(&mut input)
+    }
+}
+
+pub fn build<S: Solver + 'static>(input: &mut dyn Read) -> anyhow::Result<Box<dyn Solver>> {
+    Ok(Box::new(S::from_input(input).context("Failed to parse")?))
+}
+
+/// Register a solver for a day, with its title. Days where some parts are
+/// still `unimplemented!()` should say which are done with `parts: [..]`.
+macro_rules! register {
+    ($day:literal, $solver:ty, $title:literal) => {
+        register!($day, $solver, $title, parts: [true, true]);
+    };
+    ($day:literal, $solver:ty, $title:literal, parts: $parts:expr) => {
+        inventory::submit! {
+            $crate::problems::registry::Registration::new(
+                $day,
+                $title,
+                $parts,
+                $crate::problems::registry::build::<$solver>,
+            )
+        }
+    };
+}
+pub(crate) use register;
+
+/// The registration for `day`, if there is one
+pub fn lookup(day: usize) -> Option<&'static Registration> {
+    inventory::iter::<Registration>().find(|r| r.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problems::DAYS;
+
+    #[test]
+    fn test_registered() {
+        let mut days: Vec<usize> = inventory::iter::<Registration>().map(|r| r.day).collect();
+        days.sort();
+        // Only real days, and not the template
+        assert_eq!(days, DAYS.collect::<Vec<_>>());
+
+        let day13 = lookup(13).unwrap();
+        assert_eq!(day13.title, "Distress Signal");
+        assert_eq!(day13.parts, [true, true]);
+        assert!(lookup(26).is_none());
+    }
+}
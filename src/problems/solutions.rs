@@ -5,12 +5,16 @@ use std::{
 
 use anyhow::Context;
 
-pub trait Solver {
+use super::Answer;
+
+/// A day's solution. Solvers are shared with the threads that run each part,
+/// so they need to be `Send + Sync`.
+pub trait Solver: Send + Sync {
     fn from_input(input: impl Read) -> anyhow::Result<Self>
     where
         Self: Sized;
-    fn part_one(&self) -> String;
-    fn part_two(&self) -> String;
+    fn part_one(&self) -> anyhow::Result<Answer>;
+    fn part_two(&self) -> anyhow::Result<Answer>;
 }
 
 pub fn parse_from_read<I>(input: impl Read) -> Result<I, anyhow::Error>
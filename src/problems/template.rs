@@ -1,33 +1,24 @@
 use std::io::Read;
 
-use super::{solutions::parse_lines, Solver};
+use super::{solutions::parse_lines, Answer, Solver};
 
-pub struct Day00(Vec<i64>);
+pub struct Day00(Vec<String>);
 
-impl Day00 {
-    pub fn max(&self) -> i64 {
-        self.0.iter().copied().max().unwrap_or_default()
-    }
-
-    pub fn sum(&self) -> i64 {
-        self.0.iter().copied().sum::<i64>()
-    }
-}
+// The stub's registration goes here, so the template itself isn't a day
 
 impl Solver for Day00 {
     fn from_input(input: impl Read) -> anyhow::Result<Self> {
-        let items = parse_lines::<i64>(input)?;
+        let lines = parse_lines::<String>(input)?;
 
-        Ok(Day00(items))
+        Ok(Day00(lines))
     }
 
-    fn part_one(&self) -> String {
-        self.max();
+    fn part_one(&self) -> anyhow::Result<Answer> {
+        log::debug!("Read {} lines", self.0.len());
         unimplemented!()
     }
 
-    fn part_two(&self) -> String {
-        self.sum();
+    fn part_two(&self) -> anyhow::Result<Answer> {
         unimplemented!()
     }
 }
@@ -47,14 +38,22 @@ mod tests {
     ";
 
     #[test]
+    fn test_parse() {
+        let day = Day00::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
+        assert_eq!(day.0.len(), 3);
+    }
+
+    #[test]
+    #[ignore = "part one is not implemented yet"]
     fn test_part_one() {
         let day = Day00::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.max(), 9);
+        assert_eq!(day.part_one().unwrap(), Answer::Int(0));
     }
 
     #[test]
+    #[ignore = "part two is not implemented yet"]
     fn test_part_two() {
         let day = Day00::from_input(unindented(EXAMPLE).unwrap().as_bytes()).unwrap();
-        assert_eq!(day.sum(), 14);
+        assert_eq!(day.part_two().unwrap(), Answer::Int(0));
     }
 }
@@ -0,0 +1,707 @@
+use std::{
+    fmt,
+    io::{self, ErrorKind, Read},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{mpsc, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
+
+use crate::problems::{has_part, solver, Answer, Solver};
+
+/// What happened when running one part of a day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Answer(Answer),
+    Error(String),
+    Panic(String),
+    // The part still hits `unimplemented!()` or `todo!()`
+    Stub,
+    // The input file is missing or empty
+    NoInput,
+    // The part wasn't asked for, so it wasn't run
+    Skipped,
+    // Still running after the time limit, so it was abandoned
+    Timeout(Duration),
+    // There's no puzzle for this part, like day 25's part two
+    NoPuzzle,
+}
+
+impl Outcome {
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            Outcome::Error(_) | Outcome::Panic(_) | Outcome::Timeout(_)
+        )
+    }
+
+    pub fn is_panic(&self) -> bool {
+        matches!(self, Outcome::Panic(_))
+    }
+
+    /// What kind of outcome this is, for machine-readable output
+    pub fn status(&self) -> &'static str {
+        match self {
+            Outcome::Answer(_) => "ok",
+            Outcome::Error(_) => "error",
+            Outcome::Panic(_) => "panic",
+            Outcome::Stub => "unimplemented",
+            Outcome::NoInput => "no-input",
+            Outcome::Skipped => "skipped",
+            Outcome::Timeout(_) => "timeout",
+            Outcome::NoPuzzle => "no-puzzle",
+        }
+    }
+
+    /// A short, single-line version for use in a table
+    pub fn summary(&self) -> String {
+        match self {
+            Outcome::Answer(Answer::Grid(rows)) if rows.len() > 1 => {
+                format!("<{} lines>", rows.len())
+            }
+            Outcome::Answer(answer) => answer.to_string(),
+            Outcome::Error(_) => "ERROR".to_owned(),
+            Outcome::Panic(_) => "PANIC".to_owned(),
+            Outcome::Stub => "not implemented".to_owned(),
+            Outcome::NoInput => "no input".to_owned(),
+            Outcome::Skipped => "-".to_owned(),
+            Outcome::Timeout(_) => "TIMEOUT".to_owned(),
+            Outcome::NoPuzzle => "no puzzle".to_owned(),
+        }
+    }
+
+    /// The full text, for anything the summary leaves out
+    pub fn details(&self) -> Option<String> {
+        match self {
+            Outcome::Answer(answer) if answer.is_multiline() => Some(answer.to_string()),
+            Outcome::Error(e) => Some(format!("error: {e}")),
+            Outcome::Panic(msg) => Some(format!("panicked: {msg}")),
+            Outcome::Timeout(limit) => Some(format!("timed out after {limit:.1?}")),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self, self.details()) {
+            (Outcome::Answer(answer), _) => write!(f, "{answer}"),
+            (_, Some(details)) => f.write_str(&details),
+            (_, None) => f.write_str(&self.summary()),
+        }
+    }
+}
+
+/// How long each run of a phase took
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Samples(Vec<Duration>);
+
+impl Samples {
+    /// Time `f`, adding how long it took to the samples
+    fn time<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let value = f();
+        self.0.push(start.elapsed());
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn sorted(&self) -> Vec<Duration> {
+        let mut samples = self.0.clone();
+        samples.sort();
+        samples
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        self.0.iter().copied().min()
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.0.iter().copied().max()
+    }
+
+    /// The middle sample, or the mean of the middle two
+    pub fn median(&self) -> Option<Duration> {
+        let sorted = self.sorted();
+        let mid = sorted.len() / 2;
+        match sorted.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(sorted[mid]),
+            _ => Some((sorted[mid - 1] + sorted[mid]) / 2),
+        }
+    }
+}
+
+/// Which parts of a day to run
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Parts {
+    One,
+    Two,
+    #[default]
+    Both,
+}
+
+impl Parts {
+    /// Just the one part, 1 or 2
+    pub fn only(part: usize) -> Self {
+        if part == 1 {
+            Parts::One
+        } else {
+            Parts::Two
+        }
+    }
+
+    pub fn includes(self, part: usize) -> bool {
+        match self {
+            Parts::One => part == 1,
+            Parts::Two => part == 2,
+            Parts::Both => true,
+        }
+    }
+}
+
+impl FromStr for Parts {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Parts::One),
+            "2" => Ok(Parts::Two),
+            "both" => Ok(Parts::Both),
+            _ => bail!("Expected 1, 2, or 'both', got '{s}'"),
+        }
+    }
+}
+
+/// Timings for each phase of running a day
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Samples,
+    pub part_one: Samples,
+    pub part_two: Samples,
+}
+
+impl Timings {
+    pub fn phases(&self) -> [(&'static str, &Samples); 3] {
+        [
+            ("parse", &self.parse),
+            ("part one", &self.part_one),
+            ("part two", &self.part_two),
+        ]
+    }
+
+    /// A typical time for the whole day: the sum of each phase's median
+    pub fn total(&self) -> Duration {
+        self.phases().iter().filter_map(|(_, s)| s.median()).sum()
+    }
+}
+
+/// The results of running both parts of a day on one input
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub day: usize,
+    pub input: PathBuf,
+    // Whether the input was read and parsed, so the parts could be run
+    pub parsed: bool,
+    pub part_one: Outcome,
+    pub part_two: Outcome,
+    pub timings: Timings,
+}
+
+impl Report {
+    /// Why none of the parts could be run, when the input couldn't be read or
+    /// parsed: the outcome shared by each part that was asked for
+    pub fn input_outcome(&self) -> Option<&Outcome> {
+        if self.parsed {
+            return None;
+        }
+        [&self.part_one, &self.part_two]
+            .into_iter()
+            .find(|&outcome| *outcome != Outcome::Skipped)
+    }
+
+    pub fn has_input(&self) -> bool {
+        self.input_outcome() != Some(&Outcome::NoInput)
+    }
+
+    /// What the input is called in the output
+    pub fn input_name(&self) -> String {
+        input_name(&self.input)
+    }
+
+    pub fn is_failure(&self) -> bool {
+        self.part_one.is_failure() || self.part_two.is_failure()
+    }
+
+    pub fn is_panic(&self) -> bool {
+        self.part_one.is_panic() || self.part_two.is_panic()
+    }
+
+    /// Whether anything was given up on, though it may still be running
+    pub fn timed_out(&self) -> bool {
+        [&self.part_one, &self.part_two]
+            .iter()
+            .any(|outcome| matches!(outcome, Outcome::Timeout(_)))
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "Unknown panic".to_owned()
+    }
+}
+
+/// Run `f`, turning a panic into an `Outcome` rather than unwinding further
+fn catch<T>(f: impl FnOnce() -> T) -> Result<T, Outcome> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let msg = panic_message(payload.as_ref());
+        // The messages from `unimplemented!()` and `todo!()`
+        if msg.starts_with("not implemented") || msg.starts_with("not yet implemented") {
+            Outcome::Stub
+        } else {
+            Outcome::Panic(msg)
+        }
+    })
+}
+
+/// Run a part `runs` times, or just once if it doesn't produce an answer
+fn run_part(
+    solution: &dyn Solver,
+    part: fn(&dyn Solver) -> anyhow::Result<Answer>,
+    runs: usize,
+    samples: &mut Samples,
+) -> Outcome {
+    let answer = match samples.time(|| catch(|| part(solution))) {
+        Ok(Ok(answer)) => answer,
+        Ok(Err(e)) => return Outcome::Error(format!("{e:#}")),
+        Err(outcome) => return outcome,
+    };
+    for _ in 1..runs {
+        // Worked the first time, but a panic or error later still counts
+        match samples.time(|| catch(|| part(solution))) {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => return Outcome::Error(format!("{e:#}")),
+            Err(outcome) => return outcome,
+        }
+    }
+    Outcome::Answer(answer)
+}
+
+/// The input path that means "read standard input"
+pub const STDIN: &str = "-";
+
+fn is_stdin(path: &Path) -> bool {
+    path == Path::new(STDIN)
+}
+
+fn input_name(path: &Path) -> String {
+    if is_stdin(path) {
+        "stdin".to_owned()
+    } else {
+        path.display().to_string()
+    }
+}
+
+/// Read all of an input file, or standard input for [`STDIN`]
+fn read_input(path: &Path) -> io::Result<Vec<u8>> {
+    if is_stdin(path) {
+        let mut text = Vec::new();
+        io::stdin().lock().read_to_end(&mut text)?;
+        Ok(text)
+    } else {
+        std::fs::read(path)
+    }
+}
+
+// Solutions can recurse deeply, so give their threads as much stack as the
+// main thread usually gets
+const STACK_SIZE: usize = 8 << 20;
+
+/// Run `f` on a thread of its own, giving up on it if it's still going after
+/// `limit`. There's no way to stop the thread, so it's left to run in the
+/// background until the program exits.
+fn with_limit<T: Send + 'static>(
+    limit: Option<Duration>,
+    f: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, Outcome> {
+    let (send, recv) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            // Nobody is listening any more if it timed out
+            let _ = send.send(f());
+        });
+    if let Err(e) = spawned {
+        return Err(Outcome::Error(format!("Starting a thread: {e}")));
+    }
+
+    match limit {
+        Some(limit) => recv.recv_timeout(limit).map_err(|e| match e {
+            mpsc::RecvTimeoutError::Timeout => Outcome::Timeout(limit),
+            mpsc::RecvTimeoutError::Disconnected => Outcome::Panic("Thread died".to_owned()),
+        }),
+        None => recv
+            .recv()
+            .map_err(|_| Outcome::Panic("Thread died".to_owned())),
+    }
+}
+
+/// Parse `input` for `day` and run the chosen parts, catching any panics along
+/// the way. Each phase is run `runs` times, so the timings can be compared,
+/// and given up on if all its runs take longer than `limit`.
+pub fn run_day(
+    day: usize,
+    input: &Path,
+    parts: Parts,
+    runs: usize,
+    limit: Option<Duration>,
+) -> Report {
+    let mut timings = Timings::default();
+    // When there's nothing to run the parts on, both get the same outcome
+    let unparsed = |outcome: Outcome, timings| {
+        let part = |part| {
+            if !parts.includes(part) {
+                Outcome::Skipped
+            } else if !has_part(day, part) {
+                Outcome::NoPuzzle
+            } else {
+                outcome.clone()
+            }
+        };
+        Report {
+            day,
+            input: input.to_owned(),
+            parsed: false,
+            part_one: part(1),
+            part_two: part(2),
+            timings,
+        }
+    };
+
+    let text = match read_input(input) {
+        Ok(text) if !text.is_empty() => text,
+        Ok(_) => return unparsed(Outcome::NoInput, timings),
+        Err(e) if e.kind() == ErrorKind::NotFound => return unparsed(Outcome::NoInput, timings),
+        Err(e) => {
+            let e = Outcome::Error(format!("Reading {}: {e}", input_name(input)));
+            return unparsed(e, timings);
+        }
+    };
+
+    let parsed = with_limit(limit, move || {
+        let mut samples = Samples::default();
+        let parse = || catch(|| solver(day, text.as_slice()));
+        let mut parsed = samples.time(parse);
+        for _ in 1..runs {
+            if !matches!(parsed, Ok(Ok(_))) {
+                break;
+            }
+            parsed = samples.time(parse);
+        }
+        (parsed, samples)
+    });
+
+    let parsed = match parsed {
+        Ok((parsed, samples)) => {
+            timings.parse = samples;
+            parsed
+        }
+        Err(outcome) => Err(outcome),
+    };
+    let solution: Arc<dyn Solver> = match parsed {
+        Ok(Ok(solution)) => solution.into(),
+        Ok(Err(e)) => return unparsed(Outcome::Error(format!("{e:#}")), timings),
+        Err(outcome) => return unparsed(outcome, timings),
+    };
+
+    let run = |part, f: fn(&dyn Solver) -> anyhow::Result<Answer>, samples: &mut Samples| {
+        if !parts.includes(part) {
+            return Outcome::Skipped;
+        }
+        if !has_part(day, part) {
+            return Outcome::NoPuzzle;
+        }
+        let solution = Arc::clone(&solution);
+        let ran = with_limit(limit, move || {
+            let mut samples = Samples::default();
+            let outcome = run_part(solution.as_ref(), f, runs, &mut samples);
+            (outcome, samples)
+        });
+        match ran {
+            Ok((outcome, ran)) => {
+                *samples = ran;
+                outcome
+            }
+            Err(outcome) => outcome,
+        }
+    };
+    let part_one = run(1, |s| s.part_one(), &mut timings.part_one);
+    let part_two = run(2, |s| s.part_two(), &mut timings.part_two);
+    Report {
+        day,
+        input: input.to_owned(),
+        parsed: true,
+        part_one,
+        part_two,
+        timings,
+    }
+}
+
+/// Print rows in aligned columns, right-aligning the columns in `right`
+pub fn print_columns(headers: &[&str], rows: &[Vec<String>], right: &[usize]) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let headers: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    for row in std::iter::once(&headers).chain(rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &w))| {
+                if right.contains(&i) {
+                    format!("{cell:>w$}")
+                } else {
+                    format!("{cell:<w$}")
+                }
+            })
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
+
+/// Print the outcome of each part, with pictures starting on their own line
+pub fn print_parts(report: &Report) {
+    for (name, outcome) in [("one", &report.part_one), ("two", &report.part_two)] {
+        match outcome {
+            Outcome::Answer(answer) if answer.is_multiline() => {
+                println!("Part {name}:\n{answer}")
+            }
+            Outcome::Skipped => {}
+            _ => println!("Part {name}: {outcome}"),
+        }
+    }
+}
+
+/// Print a table of reports, followed by anything that didn't fit in it
+pub fn print_table(reports: &[Report]) {
+    let rows: Vec<Vec<String>> = reports
+        .iter()
+        .map(|r| {
+            let time = if r.has_input() {
+                format!("{:.1?}", r.timings.total())
+            } else {
+                String::new()
+            };
+            vec![
+                format!("{}", r.day),
+                r.part_one.summary(),
+                r.part_two.summary(),
+                time,
+            ]
+        })
+        .collect();
+    print_columns(&["Day", "Part one", "Part two", "Time"], &rows, &[0, 3]);
+
+    for r in reports {
+        for (name, outcome) in [("one", &r.part_one), ("two", &r.part_two)] {
+            if let Some(details) = outcome.details() {
+                println!("\nDay {} part {name}:\n{details}", r.day);
+            }
+        }
+    }
+}
+
+/// Print the min, median, and max time of each phase of each report
+pub fn print_timings(reports: &[Report]) {
+    let fmt = |d: Option<Duration>| d.map(|d| format!("{d:.1?}")).unwrap_or_default();
+    let rows: Vec<Vec<String>> = reports
+        .iter()
+        .flat_map(|r| {
+            r.timings
+                .phases()
+                .into_iter()
+                .filter(|(_, samples)| samples.len() > 0)
+                .map(|(phase, samples)| {
+                    vec![
+                        format!("{}", r.day),
+                        phase.to_owned(),
+                        format!("{}", samples.len()),
+                        fmt(samples.min()),
+                        fmt(samples.median()),
+                        fmt(samples.max()),
+                    ]
+                })
+        })
+        .collect();
+    print_columns(
+        &["Day", "Phase", "Runs", "Min", "Median", "Max"],
+        &rows,
+        &[0, 2, 3, 4, 5],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn test_catch() {
+        assert_eq!(catch(|| 3), Ok(3));
+        assert_eq!(catch(|| -> i64 { unimplemented!() }), Err(Outcome::Stub));
+        assert_eq!(catch(|| -> i64 { todo!() }), Err(Outcome::Stub));
+        assert_eq!(
+            catch(|| -> i64 { panic!("Oh no: {}", 4) }),
+            Err(Outcome::Panic("Oh no: 4".to_owned()))
+        );
+    }
+
+    struct Flaky(AtomicUsize);
+
+    impl Solver for Flaky {
+        fn from_input(_: impl std::io::Read) -> anyhow::Result<Self> {
+            Ok(Flaky(Default::default()))
+        }
+
+        fn part_one(&self) -> anyhow::Result<Answer> {
+            let run = self.0.fetch_add(1, Ordering::Relaxed) + 1;
+            if run > 2 {
+                panic!("Run {run}");
+            }
+            Ok(Answer::Int(1))
+        }
+
+        fn part_two(&self) -> anyhow::Result<Answer> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_run_part_repeats() {
+        let flaky = Flaky(Default::default());
+        let mut samples = Samples::default();
+        let outcome = run_part(&flaky, |s| s.part_one(), 5, &mut samples);
+        assert_eq!(outcome, Outcome::Panic("Run 3".to_owned()));
+        assert_eq!(samples.len(), 3);
+    }
+
+    #[test]
+    fn test_with_limit() {
+        let limit = Some(Duration::from_millis(20));
+        assert_eq!(with_limit(limit, || 3), Ok(3));
+        assert_eq!(with_limit(None, || 3), Ok(3));
+        let slow = with_limit(limit, || thread::sleep(Duration::from_secs(1)));
+        assert_eq!(slow, Err(Outcome::Timeout(Duration::from_millis(20))));
+        assert_eq!(slow.unwrap_err().status(), "timeout");
+    }
+
+    #[test]
+    fn test_summary() {
+        let crt = Outcome::Answer(Answer::grid("##..\n#..#"));
+        assert_eq!(crt.summary(), "<2 lines>");
+        assert_eq!(crt.details().unwrap(), "##..\n#..#");
+
+        let answer = Outcome::Answer(Answer::Int(42));
+        assert_eq!(answer.summary(), "42");
+        assert_eq!(answer.details(), None);
+        assert!(!answer.is_failure());
+        assert!(Outcome::Error("Failed to parse".to_owned()).is_failure());
+    }
+
+    #[test]
+    fn test_samples() {
+        let mut samples = Samples::default();
+        assert_eq!(samples.median(), None);
+        for ms in [5, 1, 3] {
+            samples.0.push(Duration::from_millis(ms));
+        }
+        assert_eq!(samples.min(), Some(Duration::from_millis(1)));
+        assert_eq!(samples.median(), Some(Duration::from_millis(3)));
+        assert_eq!(samples.max(), Some(Duration::from_millis(5)));
+        samples.0.push(Duration::from_millis(4));
+        assert_eq!(samples.median(), Some(Duration::from_micros(3500)));
+    }
+
+    #[test]
+    fn test_parts() {
+        assert_eq!("1".parse::<Parts>().unwrap(), Parts::One);
+        assert_eq!("both".parse::<Parts>().unwrap(), Parts::Both);
+        assert!("3".parse::<Parts>().is_err());
+
+        let path = std::env::temp_dir().join(format!("aoc-parts-{}.txt", std::process::id()));
+        std::fs::write(&path, "1000\n2000\n\n4000\n").unwrap();
+        let report = run_day(1, &path, Parts::Two, 1, None);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(report.part_one, Outcome::Skipped);
+        assert_eq!(report.part_two, Outcome::Answer(Answer::Int(7000)));
+        assert_eq!(report.timings.part_one.len(), 0);
+
+        // Parts that weren't asked for are skipped even without an input
+        let report = run_day(
+            1,
+            Path::new("inputs/does-not-exist.txt"),
+            Parts::One,
+            1,
+            None,
+        );
+        assert_eq!(report.part_one, Outcome::NoInput);
+        assert_eq!(report.part_two, Outcome::Skipped);
+        assert_eq!(report.input_outcome(), Some(&Outcome::NoInput));
+        assert!(!report.has_input());
+
+        std::fs::write(&path, "1000\nabc\n").unwrap();
+        let report = run_day(1, &path, Parts::Two, 1, None);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(report.part_one, Outcome::Skipped);
+        assert!(report.part_two.is_failure(), "{}", report.part_two);
+        assert!(report.has_input());
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let report = run_day(
+            1,
+            Path::new("inputs/does-not-exist.txt"),
+            Parts::Both,
+            1,
+            None,
+        );
+        assert_eq!(report.part_one, Outcome::NoInput);
+        assert_eq!(report.part_two, Outcome::NoInput);
+        assert!(!report.is_failure());
+        assert_eq!(report.input_name(), "inputs/does-not-exist.txt");
+        assert_eq!(input_name(Path::new(STDIN)), "stdin");
+
+        // Day 25's part two has no puzzle, input or not
+        let report = run_day(
+            25,
+            Path::new("inputs/does-not-exist.txt"),
+            Parts::Both,
+            1,
+            None,
+        );
+        assert_eq!(report.part_one, Outcome::NoInput);
+        assert_eq!(report.part_two, Outcome::NoPuzzle);
+        assert!(!report.is_failure());
+    }
+}
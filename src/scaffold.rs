@@ -0,0 +1,172 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+
+const TEMPLATE: &str = include_str!("problems/template.rs");
+const TEMPLATE_IMPORTS: &str = "use super::{solutions::parse_lines, Answer, Solver};";
+const TEMPLATE_REGISTRATION: &str =
+    "// The stub's registration goes here, so the template itself isn't a day";
+const MODULES: &str = "src/problems/mod.rs";
+
+pub fn source_path(day: usize) -> PathBuf {
+    format!("src/problems/day{:02}.rs", day).into()
+}
+
+pub fn example_path(day: usize) -> PathBuf {
+    format!("inputs/examples/day{:02}.txt", day).into()
+}
+
+/// The template, made into a stub for `day`
+pub fn render(day: usize, title: &str) -> String {
+    let registration = format!("register!({day}, Day{day:02}, {title:?}, parts: [false, false]);");
+    TEMPLATE
+        .replace(
+            TEMPLATE_IMPORTS,
+            "use super::{registry::register, solutions::parse_lines, Answer, Solver};",
+        )
+        .replace(TEMPLATE_REGISTRATION, &registration)
+        .replace("Day00", &format!("Day{day:02}"))
+}
+
+/// Add `mod dayNN;` to the list of modules, keeping the days in order
+pub fn add_module(modules: &str, day: usize) -> anyhow::Result<String> {
+    let line = format!("mod day{day:02};");
+    let mut lines: Vec<&str> = modules.lines().collect();
+    if lines.contains(&line.as_str()) {
+        return Ok(modules.to_owned());
+    }
+
+    let is_day = |l: &&str| l.starts_with("mod day") && l.ends_with(';');
+    let Some(last) = lines.iter().rposition(is_day) else {
+        bail!("Couldn't find where the days are declared");
+    };
+    let at = lines
+        .iter()
+        .position(|l| is_day(l) && *l > line.as_str())
+        .unwrap_or(last + 1);
+    lines.insert(at, &line);
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    Ok(updated)
+}
+
+/// Whether a day's source is still a stub, with both parts left as
+/// `unimplemented!()`, like the ones [`render`] makes
+pub fn is_stub(source: &str) -> bool {
+    ["fn part_one", "fn part_two"].iter().all(|part| {
+        source.split_once(part).is_some_and(|(_, rest)| {
+            let body = rest.find("fn ").map_or(rest, |end| &rest[..end]);
+            body.contains("unimplemented!()")
+        })
+    })
+}
+
+/// Create a file if it doesn't exist yet, leaving any existing one alone
+fn touch(path: &Path) -> anyhow::Result<bool> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(_) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("Creating {}", path.display())),
+    }
+}
+
+/// Set up a new day from the template: its source file, its module
+/// declaration, and empty files for its input and example. Returns the files
+/// that were created or changed.
+///
+/// An existing day is never replaced if any of its parts are done, going by
+/// what's on disk, and a stub only with `force`. Everything that might fail
+/// is read before anything is written.
+pub fn new_day(day: usize, title: &str, input: &Path, force: bool) -> anyhow::Result<Vec<PathBuf>> {
+    let source = source_path(day);
+    match fs::read_to_string(&source) {
+        Ok(existing) if !is_stub(&existing) => {
+            bail!("Day {day} is already implemented in {}", source.display());
+        }
+        Ok(_) if !force => bail!(
+            "{} already exists, use --force to replace the stub",
+            source.display()
+        ),
+        Ok(_) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e).with_context(|| format!("Reading {}", source.display())),
+    }
+    let modules = fs::read_to_string(MODULES).with_context(|| format!("Reading {MODULES}"))?;
+    let updated = add_module(&modules, day)?;
+
+    let mut changed = Vec::new();
+    fs::write(&source, render(day, title))
+        .with_context(|| format!("Writing {}", source.display()))?;
+    changed.push(source);
+
+    if updated != modules {
+        fs::write(MODULES, updated).with_context(|| format!("Writing {MODULES}"))?;
+        changed.push(MODULES.into());
+    }
+
+    for path in [input.to_owned(), example_path(day)] {
+        if touch(&path)? {
+            changed.push(path);
+        }
+    }
+
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let stub = render(7, "No Space Left On Device");
+        assert!(stub.contains("pub struct Day07(Vec<String>);"));
+        assert!(stub
+            .contains(r#"register!(7, Day07, "No Space Left On Device", parts: [false, false]);"#));
+        assert!(!stub.contains("Day00"));
+        assert!(stub.contains("use super::{registry::register,"));
+        assert!(!stub.contains(TEMPLATE_REGISTRATION));
+    }
+
+    #[test]
+    fn test_is_stub() {
+        assert!(is_stub(&render(7, "No Space Left On Device")));
+        assert!(is_stub(TEMPLATE));
+
+        // Only one part left to do, or a real day whose flags were never
+        // updated, isn't a stub
+        let half_done = TEMPLATE.replacen("unimplemented!()", "Ok(Answer::Int(0))", 1);
+        assert!(!is_stub(&half_done));
+        let day13 = fs::read_to_string(source_path(13)).unwrap();
+        assert!(!is_stub(&day13));
+    }
+
+    #[test]
+    fn test_add_module() {
+        let modules = "mod day01;\nmod day03;\n\nmod solutions;\n";
+        assert_eq!(
+            add_module(modules, 2).unwrap(),
+            "mod day01;\nmod day02;\nmod day03;\n\nmod solutions;\n"
+        );
+        assert_eq!(
+            add_module(modules, 4).unwrap(),
+            "mod day01;\nmod day03;\nmod day04;\n\nmod solutions;\n"
+        );
+        assert_eq!(add_module(modules, 3).unwrap(), modules);
+        assert!(add_module("mod solutions;\n", 1).is_err());
+
+        // The real list of modules can be updated too
+        let modules = fs::read_to_string(MODULES).unwrap();
+        assert_eq!(add_module(&modules, 25).unwrap(), modules);
+    }
+}
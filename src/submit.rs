@@ -0,0 +1,463 @@
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context};
+
+use crate::{
+    client::Client,
+    format::{escape, unescape},
+    problems::{has_part, Answer},
+};
+
+/// Every answer we've submitted, and what the site said about it
+pub const LEDGER_PATH: &str = "answers/submissions.tsv";
+
+/// What the site said about a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Too soon after the last attempt; try again after the wait, if the site
+    /// said how long that is
+    RateLimited(Option<Duration>),
+    /// The part has already been solved, so the answer wasn't checked
+    AlreadySolved,
+    /// Anything else, with the message from the page
+    Unknown(String),
+}
+
+/// The text of the page's message, with the HTML tags left out
+fn message(page: &str) -> String {
+    // Starting after "<article", so still inside its opening tag
+    let (article, mut in_tag) = match page
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+    {
+        Some((article, _)) => (article, true),
+        None => (page, false),
+    };
+
+    let mut text = String::new();
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parse a wait like "1m 23s" out of "You have 1m 23s left to wait."
+fn parse_wait(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+    let mut seconds = 0;
+    for word in wait.split_whitespace() {
+        let (n, unit) = word.split_at(word.find(|c: char| !c.is_ascii_digit())?);
+        let n: u64 = n.parse().ok()?;
+        seconds += match unit {
+            "h" => n * 3600,
+            "m" => n * 60,
+            "s" => n,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+impl Verdict {
+    /// Work out the verdict from the page the site sends back
+    pub fn parse(page: &str) -> Self {
+        let text = message(page);
+        if text.contains("That's the right answer") {
+            Verdict::Correct
+        } else if text.contains("You gave an answer too recently") {
+            Verdict::RateLimited(parse_wait(&text))
+        } else if text.contains("Did you already complete it?") {
+            Verdict::AlreadySolved
+        } else if text.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if text.contains("your answer is too low") {
+            Verdict::TooLow
+        } else if text.contains("That's not the right answer") {
+            Verdict::Wrong
+        } else {
+            Verdict::Unknown(text)
+        }
+    }
+
+    /// Whether the answer is known to be wrong
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+
+    /// How it's written in the ledger
+    fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::Wrong => "wrong",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::RateLimited(_) => "rate-limited",
+            Verdict::AlreadySolved => "already-solved",
+            Verdict::Unknown(_) => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => f.write_str("That's the right answer!"),
+            Verdict::Wrong => f.write_str("That's not the right answer"),
+            Verdict::TooHigh => f.write_str("That's not the right answer, it's too high"),
+            Verdict::TooLow => f.write_str("That's not the right answer, it's too low"),
+            Verdict::RateLimited(Some(wait)) => {
+                write!(f, "Answered too recently, wait {}s", wait.as_secs())
+            }
+            Verdict::RateLimited(None) => f.write_str("Answered too recently"),
+            Verdict::AlreadySolved => f.write_str("That part is already solved"),
+            Verdict::Unknown(text) => write!(f, "Unexpected response: {text}"),
+        }
+    }
+}
+
+/// One submitted answer, as a line of the ledger:
+/// `time  day  part  verdict  wait  message  answer`, separated by tabs, with
+/// the time in seconds since the epoch, the wait in seconds (or `-`), and the
+/// message for an unknown verdict. The message and answer are escaped like
+/// TSV output, so they can hold tabs and newlines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub time: u64,
+    pub day: usize,
+    pub part: usize,
+    /// The answer exactly as it was sent
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let wait = match &self.verdict {
+            Verdict::RateLimited(Some(wait)) => wait.as_secs().to_string(),
+            _ => "-".to_owned(),
+        };
+        let message = match &self.verdict {
+            Verdict::Unknown(text) => escape(text),
+            _ => String::new(),
+        };
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{wait}\t{message}\t{}",
+            self.time,
+            self.day,
+            self.part,
+            self.verdict.name(),
+            escape(&self.answer)
+        )
+    }
+}
+
+impl FromStr for Attempt {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split('\t').collect();
+        let &[time, day, part, verdict, wait, message, answer] = fields.as_slice() else {
+            bail!("Expected 7 tab-separated fields, got '{s}'");
+        };
+        let verdict = match verdict {
+            "correct" => Verdict::Correct,
+            "wrong" => Verdict::Wrong,
+            "too-high" => Verdict::TooHigh,
+            "too-low" => Verdict::TooLow,
+            "rate-limited" if wait == "-" => Verdict::RateLimited(None),
+            "rate-limited" => Verdict::RateLimited(Some(Duration::from_secs(wait.parse()?))),
+            "already-solved" => Verdict::AlreadySolved,
+            "unknown" => Verdict::Unknown(unescape(message)),
+            _ => bail!("Unknown verdict '{verdict}'"),
+        };
+        Ok(Attempt {
+            time: time.parse()?,
+            day: day.parse()?,
+            part: part.parse()?,
+            answer: unescape(answer),
+            verdict,
+        })
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// The answers submitted so far
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ledger(pub Vec<Attempt>);
+
+impl Ledger {
+    /// Read the ledger at `path`, which is empty if there's no file yet
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Ledger::default());
+        }
+        let text = fs::read_to_string(path)?;
+        let attempts = text
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(Attempt::from_str)
+            .collect::<anyhow::Result<_>>()
+            .with_context(|| format!("Reading {}", path.display()))?;
+        Ok(Ledger(attempts))
+    }
+
+    /// Add an attempt to the end of the ledger at `path`
+    pub fn append(path: &Path, attempt: &Attempt) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Opening {}", path.display()))?;
+        writeln!(file, "{attempt}").with_context(|| format!("Writing {}", path.display()))
+    }
+
+    /// Why `answer` shouldn't be submitted for this part at time `now`, if
+    /// the earlier attempts already tell us what the site would say. Answers
+    /// are compared as the exact text sent, and as numbers for the bounds.
+    pub fn refusal(&self, day: usize, part: usize, answer: &str, now: u64) -> Option<String> {
+        let attempts = self.0.iter().filter(|a| a.day == day && a.part == part);
+        for attempt in attempts {
+            match &attempt.verdict {
+                Verdict::Correct => {
+                    return Some(format!("Already solved, with {}", attempt.answer));
+                }
+                Verdict::RateLimited(Some(wait)) if attempt.time + wait.as_secs() > now => {
+                    let left = attempt.time + wait.as_secs() - now;
+                    return Some(format!("Rate limited, wait another {left}s"));
+                }
+                verdict if verdict.is_wrong() && attempt.answer == *answer => {
+                    return Some(format!("{answer} was already tried: {verdict}"));
+                }
+                _ => {}
+            }
+
+            let (Ok(tried), Ok(n)) = (attempt.answer.parse::<i128>(), answer.parse::<i128>())
+            else {
+                continue;
+            };
+            match attempt.verdict {
+                Verdict::TooHigh if n >= tried => {
+                    return Some(format!("{n} is too high, as {tried} already was"));
+                }
+                Verdict::TooLow if n <= tried => {
+                    return Some(format!("{n} is too low, as {tried} already was"));
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// Submit `answer` for one part of a day, unless the ledger at `ledger`
+/// already says how that would go, and record the attempt there
+pub fn submit(
+    client: &Client,
+    ledger: &Path,
+    day: usize,
+    part: usize,
+    answer: &Answer,
+) -> anyhow::Result<Verdict> {
+    if !has_part(day, part) {
+        bail!("Day {day} has no part {part} to submit");
+    }
+    if answer.is_multiline() {
+        bail!("Can't submit a picture; read it and submit the letters instead");
+    }
+    let answer = answer.to_string();
+    let now = now();
+    if let Some(reason) = Ledger::load(ledger)?.refusal(day, part, &answer, now) {
+        return Err(anyhow!(reason).context("Not submitting"));
+    }
+
+    let verdict = Verdict::parse(&client.answer(day, part, &answer)?);
+    let attempt = Attempt {
+        time: now,
+        day,
+        part,
+        answer,
+        verdict: verdict.clone(),
+    };
+    Ledger::append(ledger, &attempt)?;
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use test_log::test;
+
+    use super::*;
+    use crate::client::tests::mock_server;
+
+    fn page(text: &str) -> String {
+        format!("<html><body><main>\n<article><p>{text}</p></article>\n</main></body></html>")
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        let parse = |text: &str| Verdict::parse(&page(text));
+        assert_eq!(
+            parse("That's the right answer! You are <em>one gold star</em> closer."),
+            Verdict::Correct
+        );
+        assert_eq!(
+            parse("That's not the right answer. If you're stuck, ..."),
+            Verdict::Wrong
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too high. Please wait..."),
+            Verdict::TooHigh
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too low."),
+            Verdict::TooLow
+        );
+        assert_eq!(
+            parse("You gave an answer too recently. You have 1m 23s left to wait."),
+            Verdict::RateLimited(Some(Duration::from_secs(83)))
+        );
+        assert_eq!(
+            parse("You gave an answer too recently. You have 45s left to wait."),
+            Verdict::RateLimited(Some(Duration::from_secs(45)))
+        );
+        assert_eq!(
+            parse("You don't seem to be solving the right level.  Did you already complete it?"),
+            Verdict::AlreadySolved
+        );
+        assert_eq!(
+            parse("Something <a href=\"/\">else</a>"),
+            Verdict::Unknown("Something else".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_ledger_lines() {
+        let attempt = Attempt {
+            time: 1670000000,
+            day: 3,
+            part: 2,
+            answer: "CMZ".to_owned(),
+            verdict: Verdict::RateLimited(Some(Duration::from_secs(30))),
+        };
+        let line = attempt.to_string();
+        assert_eq!(line, "1670000000\t3\t2\trate-limited\t30\t\tCMZ");
+        assert_eq!(line.parse::<Attempt>().unwrap(), attempt);
+
+        // The site's message and the answer are kept, whatever's in them
+        let attempt = Attempt {
+            answer: "a\tb".to_owned(),
+            verdict: Verdict::Unknown("Huh?\tWhat".to_owned()),
+            ..attempt
+        };
+        let line = attempt.to_string();
+        assert_eq!(line, "1670000000\t3\t2\tunknown\t-\tHuh?\\tWhat\ta\\tb");
+        assert_eq!(line.parse::<Attempt>().unwrap(), attempt);
+        assert!("1\t2\t3".parse::<Attempt>().is_err());
+    }
+
+    #[test]
+    fn test_refusal() {
+        let attempt = |part, answer: i64, verdict| Attempt {
+            time: 100,
+            day: 1,
+            part,
+            answer: answer.to_string(),
+            verdict,
+        };
+        let ledger = Ledger(vec![
+            attempt(1, 50, Verdict::TooHigh),
+            attempt(1, 10, Verdict::TooLow),
+            attempt(1, 20, Verdict::Wrong),
+            attempt(2, 7, Verdict::Correct),
+        ]);
+        let refused =
+            |part, answer: i64| ledger.refusal(1, part, &answer.to_string(), 200).is_some();
+
+        assert!(refused(1, 50));
+        assert!(refused(1, 60));
+        assert!(refused(1, 10));
+        assert!(refused(1, 5));
+        assert!(refused(1, 20));
+        assert!(!refused(1, 30));
+        assert!(refused(2, 8));
+        // Other days aren't affected
+        assert!(ledger.refusal(2, 1, "60", 200).is_none());
+
+        // A digit-only SNAFU answer read back from the ledger still matches
+        // the text the solver gives
+        let snafu = Ledger(vec!["100\t25\t1\twrong\t-\t\t1121".parse().unwrap()]);
+        let answer = Answer::from("1121").to_string();
+        assert!(snafu.refusal(25, 1, &answer, 200).is_some());
+        assert!(snafu.refusal(25, 1, "1=11", 200).is_none());
+
+        let limited = Ledger(vec![attempt(
+            1,
+            30,
+            Verdict::RateLimited(Some(Duration::from_secs(60))),
+        )]);
+        assert!(limited.refusal(1, 1, "31", 150).is_some());
+        assert!(limited.refusal(1, 1, "31", 160).is_none());
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, requests) = mock_server(vec![
+            (
+                200,
+                page("That's not the right answer; your answer is too low."),
+            ),
+            (200, page("That's the right answer!")),
+        ]);
+        let client = Client::new(&url, "abc123");
+        let dir = env::temp_dir().join(format!("aoc-submit-{}", std::process::id()));
+        let ledger = dir.join("submissions.tsv");
+
+        let verdict = submit(&client, &ledger, 4, 1, &Answer::Int(12)).unwrap();
+        assert_eq!(verdict, Verdict::TooLow);
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line.trim_end(), "POST /2022/day/4/answer HTTP/1.1");
+        assert_eq!(request.header("Cookie"), Some("session=abc123"));
+        assert_eq!(request.body, "level=1&answer=12");
+
+        // Known to be too low, so it's not sent
+        assert!(submit(&client, &ledger, 4, 1, &Answer::Int(11)).is_err());
+        // Day 25 has nothing to submit for part two
+        assert!(submit(&client, &ledger, 25, 2, &Answer::from("Merry Christmas")).is_err());
+
+        let verdict = submit(&client, &ledger, 4, 1, &Answer::Int(13)).unwrap();
+        assert_eq!(verdict, Verdict::Correct);
+
+        let Ledger(attempts) = Ledger::load(&ledger).unwrap();
+        let verdicts: Vec<_> = attempts.iter().map(|a| a.verdict.clone()).collect();
+        assert_eq!(verdicts, vec![Verdict::TooLow, Verdict::Correct]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}